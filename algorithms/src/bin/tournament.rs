@@ -0,0 +1,63 @@
+use std::{env, fs, process};
+
+use algorithms::{
+    strategies,
+    tournament::{leaderboard, parse_mazes, parse_teams, run, to_csv, MazeConfig, TeamConfig, TournamentConfig},
+};
+use shared::functions::get_arg_value;
+
+// Tournoi toutes-rondes entre stratégies, sur plusieurs labyrinthes, à travers le simulateur :
+// `cargo run -p algorithms --bin tournament -- --teams a=client,b=right-hand --mazes 10x10:1-20`
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let fail = |message: String| -> ! {
+        eprintln!("{message}");
+        process::exit(2);
+    };
+    let number = |flag: &str, default: u64| {
+        get_arg_value(&args, flag).map_or(default, |value| {
+            value.parse().unwrap_or_else(|_| fail(format!("Valeur invalide pour {flag}: {value}")))
+        })
+    };
+
+    // Par défaut une équipe par stratégie, sur dix labyrinthes 10x10
+    let teams = match get_arg_value(&args, "--teams") {
+        Some(spec) => parse_teams(&spec).unwrap_or_else(|err| fail(format!("--teams: {err}"))),
+        None => strategies::NAMES
+            .iter()
+            .map(|name| TeamConfig {
+                name: name.to_string(),
+                strategy: name.to_string(),
+            })
+            .collect(),
+    };
+    let mazes = match get_arg_value(&args, "--mazes") {
+        Some(spec) => parse_mazes(&spec).unwrap_or_else(|err| fail(format!("--mazes: {err}"))),
+        None => (0..10).map(|seed| MazeConfig { width: 10, height: 10, seed }).collect(),
+    };
+
+    let config = TournamentConfig {
+        teams,
+        mazes,
+        players_per_team: number("--players", 3) as usize,
+        max_turns: number("--max-turns", 1000),
+        challenge_every: get_arg_value(&args, "--challenge-every").and_then(|value| value.parse().ok()),
+    };
+
+    let results = run(&config).unwrap_or_else(|err| fail(format!("Tournoi impossible: {err}")));
+    let standings = leaderboard(&config, &results);
+
+    // Le client écrit ses décisions sur la sortie standard : le classement va dans un fichier
+    let format = get_arg_value(&args, "--format").unwrap_or(String::from("csv"));
+    let output = match format.as_str() {
+        "csv" => to_csv(&standings),
+        "json" => serde_json::to_string_pretty(&standings).expect("standings are serializable"),
+        other => fail(format!("Format inconnu: {other} (csv ou json)")),
+    };
+    let path = get_arg_value(&args, "--output").unwrap_or(format!("leaderboard.{format}"));
+
+    if let Err(err) = fs::write(&path, output) {
+        fail(format!("Impossible d'écrire {path}: {err}"));
+    }
+    println!("{} parties jouées, classement écrit dans {path}", results.len());
+}
@@ -1,4 +1,6 @@
+pub mod strategies;
+pub mod tournament;
 
 pub fn add(left: u64, right: u64) -> u64 {  
     left + right
-}
\ No newline at end of file
+}
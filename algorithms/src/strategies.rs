@@ -0,0 +1,138 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use shared::{
+    enums::{Action, Message},
+    game_engine::Direction,
+    radar_view::{RadarView, WallState},
+    simulator::{ClientStrategy, Strategy},
+};
+
+// Stratégies disponibles pour les tournois, par nom
+pub const NAMES: [&str; 4] = ["client", "right-hand", "left-hand", "random"];
+
+// Crée la stratégie `name` pour une équipe de `players` joueurs. `seed` rend les stratégies
+// aléatoires reproductibles
+pub fn by_name(name: &str, players: usize, seed: u64) -> Option<Box<dyn Strategy>> {
+    let strategy: Box<dyn Strategy> = match name {
+        "client" => Box::new(ClientStrategy::new(players)),
+        "right-hand" => Box::new(WallFollower::new(Hand::Right, players)),
+        "left-hand" => Box::new(WallFollower::new(Hand::Left, players)),
+        "random" => Box::new(RandomWalk::new(players, seed)),
+        _ => return None,
+    };
+    Some(strategy)
+}
+
+// Murs autour du joueur dans la vue radar (case centrale)
+fn is_open(radar: &RadarView, direction: Direction) -> bool {
+    let wall = match direction {
+        Direction::Front => radar.horizontal[3 + 1],
+        Direction::Back => radar.horizontal[2 * 3 + 1],
+        Direction::Left => radar.vertical[4 + 1],
+        Direction::Right => radar.vertical[4 + 2],
+    };
+    wall == WallState::Open
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+// Suit un mur (main droite ou gauche) ; les secrets et challenges sont gérés par le client
+pub struct WallFollower {
+    hand: Hand,
+    client: ClientStrategy,
+}
+
+impl WallFollower {
+    pub fn new(hand: Hand, players: usize) -> Self {
+        Self {
+            hand,
+            client: ClientStrategy::new(players),
+        }
+    }
+}
+
+impl Strategy for WallFollower {
+    fn handle(&mut self, player: usize, message: &Message) -> Vec<Message> {
+        let Message::RadarView(radar) = message else {
+            return self.client.handle(player, message);
+        };
+
+        let order = match self.hand {
+            Hand::Right => [Direction::Right, Direction::Front, Direction::Left],
+            Hand::Left => [Direction::Left, Direction::Front, Direction::Right],
+        };
+        let direction = order
+            .into_iter()
+            .find(|&direction| is_open(radar, direction))
+            .unwrap_or(Direction::Back);
+        vec![Message::Action(Action::MoveTo(direction))]
+    }
+}
+
+// Avance au hasard parmi les passages ouverts, sans faire demi-tour sauf dans une impasse
+pub struct RandomWalk {
+    rng: StdRng,
+    client: ClientStrategy,
+}
+
+impl RandomWalk {
+    pub fn new(players: usize, seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            client: ClientStrategy::new(players),
+        }
+    }
+}
+
+impl Strategy for RandomWalk {
+    fn handle(&mut self, player: usize, message: &Message) -> Vec<Message> {
+        let Message::RadarView(radar) = message else {
+            return self.client.handle(player, message);
+        };
+
+        let open: Vec<Direction> = [Direction::Front, Direction::Left, Direction::Right]
+            .into_iter()
+            .filter(|&direction| is_open(radar, direction))
+            .collect();
+        let direction = if open.is_empty() {
+            Direction::Back
+        } else {
+            open[self.rng.random_range(0..open.len())]
+        };
+        vec![Message::Action(Action::MoveTo(direction))]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::radar_view::decode_radarview;
+
+    fn chosen(strategy: &mut dyn Strategy, message: &Message) -> Direction {
+        match strategy.handle(0, message).as_slice() {
+            [Message::Action(Action::MoveTo(direction))] => *direction,
+            other => panic!("{other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_strategies_pick_open_sides() {
+        // Tous les murs horizontaux fermés, tous les murs verticaux ouverts
+        let radar = Message::RadarView(decode_radarview("QQQQvvvv9iasmpa").unwrap());
+
+        assert!(matches!(chosen(&mut WallFollower::new(Hand::Right, 1), &radar), Direction::Right));
+        assert!(matches!(chosen(&mut WallFollower::new(Hand::Left, 1), &radar), Direction::Left));
+        assert!(matches!(chosen(&mut RandomWalk::new(1, 0), &radar), Direction::Left | Direction::Right));
+    }
+
+    #[test]
+    fn test_by_name() {
+        for name in NAMES {
+            assert!(by_name(name, 3, 0).is_some(), "{name}");
+        }
+        assert!(by_name("teleport", 3, 0).is_none());
+    }
+}
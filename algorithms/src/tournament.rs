@@ -0,0 +1,339 @@
+use std::{collections::HashSet, fmt};
+
+use serde::{Deserialize, Serialize};
+use shared::simulator::{simulate, SimulationConfig, Strategy, TeamReport};
+
+use crate::strategies;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TeamConfig {
+    pub name: String,
+    pub strategy: String, // Nom d'une stratégie de `strategies::NAMES`
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MazeConfig {
+    pub width: usize,
+    pub height: usize,
+    pub seed: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TournamentConfig {
+    pub teams: Vec<TeamConfig>,
+    pub mazes: Vec<MazeConfig>,
+    pub players_per_team: usize,
+    pub max_turns: u64,
+    pub challenge_every: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TournamentError {
+    UnknownStrategy(String),
+    DuplicateTeam(String),
+    InvalidSpec(String), // Liste d'équipes ou de labyrinthes mal écrite
+}
+
+impl fmt::Display for TournamentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TournamentError::UnknownStrategy(name) => {
+                write!(f, "unknown strategy {name:?}, expected one of {:?}", strategies::NAMES)
+            }
+            TournamentError::DuplicateTeam(name) => write!(f, "team {name:?} is listed twice"),
+            TournamentError::InvalidSpec(spec) => write!(f, "invalid specification {spec:?}"),
+        }
+    }
+}
+
+impl std::error::Error for TournamentError {}
+
+// Une partie du tournoi : les équipes engagées dans l'ordre de jeu et leurs rapports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchResult {
+    pub maze: MazeConfig,
+    pub teams: Vec<TeamReport>,
+    pub winner: Option<String>, // L'équipe sortie en le moins de déplacements, aucune en cas d'égalité
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Standing {
+    pub name: String,
+    pub strategy: String,
+    pub matches: u64,
+    pub wins: u64,
+    pub win_rate: f64,
+    pub timeouts: u64, // Parties terminées sans atteindre la sortie
+    pub mean_moves: Option<f64>, // Déplacements jusqu'à la sortie, sur les parties réussies
+    pub median_moves: Option<f64>,
+}
+
+// Équipes au format `nom=stratégie,nom=stratégie`
+pub fn parse_teams(spec: &str) -> Result<Vec<TeamConfig>, TournamentError> {
+    spec.split(',')
+        .map(|team| match team.trim().split_once('=') {
+            Some((name, strategy)) if !name.is_empty() => Ok(TeamConfig {
+                name: name.to_string(),
+                strategy: strategy.to_string(),
+            }),
+            _ => Err(TournamentError::InvalidSpec(team.to_string())),
+        })
+        .collect()
+}
+
+// Labyrinthes au format `LARGEURxHAUTEUR:graine` ou `LARGEURxHAUTEUR:première-dernière`
+pub fn parse_mazes(spec: &str) -> Result<Vec<MazeConfig>, TournamentError> {
+    let mut mazes = Vec::new();
+    for maze in spec.split(',') {
+        let invalid = || TournamentError::InvalidSpec(maze.to_string());
+        let (size, seeds) = maze.trim().split_once(':').ok_or_else(invalid)?;
+        let (width, height) = size.split_once('x').ok_or_else(invalid)?;
+        let (width, height): (usize, usize) =
+            (width.parse().map_err(|_| invalid())?, height.parse().map_err(|_| invalid())?);
+        let (first, last) = seeds.split_once('-').unwrap_or((seeds, seeds));
+        let (first, last): (u64, u64) = (first.parse().map_err(|_| invalid())?, last.parse().map_err(|_| invalid())?);
+        // Pas de labyrinthe vide, ni d'intervalle de graines à l'envers
+        if width == 0 || height == 0 || first > last {
+            return Err(invalid());
+        }
+
+        mazes.extend((first..=last).map(|seed| MazeConfig { width, height, seed }));
+    }
+    Ok(mazes)
+}
+
+// Rencontres d'un tournoi : chaque paire d'équipes joue deux fois (chaque équipe joue une fois
+// en premier), une équipe seule joue contre le labyrinthe
+pub fn fixtures(teams: usize) -> Vec<Vec<usize>> {
+    if teams < 2 {
+        return (0..teams).map(|team| vec![team]).collect();
+    }
+
+    let mut fixtures = Vec::new();
+    for first in 0..teams {
+        for second in 0..teams {
+            if first != second {
+                fixtures.push(vec![first, second]);
+            }
+        }
+    }
+    fixtures
+}
+
+fn winner(reports: &[TeamReport]) -> Option<String> {
+    let best = reports.iter().filter_map(|report| report.moves_to_exit).min()?;
+    let mut winners = reports.iter().filter(|report| report.moves_to_exit == Some(best));
+
+    match (winners.next(), winners.next()) {
+        (Some(report), None) => Some(report.name.clone()),
+        _ => None,
+    }
+}
+
+// Joue toutes les rencontres sur tous les labyrinthes
+pub fn run(config: &TournamentConfig) -> Result<Vec<MatchResult>, TournamentError> {
+    let mut names = HashSet::new();
+    for team in &config.teams {
+        if !strategies::NAMES.contains(&team.strategy.as_str()) {
+            return Err(TournamentError::UnknownStrategy(team.strategy.clone()));
+        }
+        if !names.insert(&team.name) {
+            return Err(TournamentError::DuplicateTeam(team.name.clone()));
+        }
+    }
+
+    let mut results = Vec::new();
+    for maze in &config.mazes {
+        for fixture in fixtures(config.teams.len()) {
+            let simulation = SimulationConfig {
+                width: maze.width,
+                height: maze.height,
+                players_per_team: config.players_per_team,
+                max_turns: config.max_turns,
+                challenge_every: config.challenge_every,
+                seed: maze.seed,
+                ..SimulationConfig::default()
+            };
+
+            // Les stratégies aléatoires dépendent du labyrinthe et de la place de l'équipe
+            let teams: Vec<(String, Box<dyn Strategy>)> = fixture
+                .iter()
+                .enumerate()
+                .map(|(slot, &team)| {
+                    let team = &config.teams[team];
+                    let seed = maze.seed.wrapping_mul(31).wrapping_add(slot as u64);
+                    let strategy = strategies::by_name(&team.strategy, config.players_per_team, seed)
+                        .expect("strategy names are checked above");
+                    (team.name.clone(), strategy)
+                })
+                .collect();
+
+            let report = simulate(simulation, teams);
+            results.push(MatchResult {
+                maze: *maze,
+                winner: winner(&report.teams),
+                teams: report.teams,
+            });
+        }
+    }
+    Ok(results)
+}
+
+fn median(sorted: &[u64]) -> Option<f64> {
+    match sorted.len() {
+        0 => None,
+        len if len % 2 == 1 => Some(sorted[len / 2] as f64),
+        len => Some((sorted[len / 2 - 1] + sorted[len / 2]) as f64 / 2.0),
+    }
+}
+
+// Classement : meilleur taux de victoire, puis moins de déplacements en moyenne, puis moins de parties sans sortie
+pub fn leaderboard(config: &TournamentConfig, results: &[MatchResult]) -> Vec<Standing> {
+    let mut standings: Vec<Standing> = config
+        .teams
+        .iter()
+        .map(|team| {
+            let reports: Vec<&TeamReport> = results
+                .iter()
+                .flat_map(|result| &result.teams)
+                .filter(|report| report.name == team.name)
+                .collect();
+            let mut moves: Vec<u64> = reports.iter().filter_map(|report| report.moves_to_exit).collect();
+            moves.sort_unstable();
+
+            let matches = reports.len() as u64;
+            let wins = results
+                .iter()
+                .filter(|result| result.winner.as_ref() == Some(&team.name))
+                .count() as u64;
+
+            Standing {
+                name: team.name.clone(),
+                strategy: team.strategy.clone(),
+                matches,
+                wins,
+                win_rate: if matches == 0 { 0.0 } else { wins as f64 / matches as f64 },
+                timeouts: matches - moves.len() as u64,
+                mean_moves: (!moves.is_empty()).then(|| moves.iter().sum::<u64>() as f64 / moves.len() as f64),
+                median_moves: median(&moves),
+            }
+        })
+        .collect();
+
+    standings.sort_by(|a, b| {
+        b.win_rate
+            .total_cmp(&a.win_rate)
+            .then_with(|| a.mean_moves.unwrap_or(f64::INFINITY).total_cmp(&b.mean_moves.unwrap_or(f64::INFINITY)))
+            .then_with(|| a.timeouts.cmp(&b.timeouts))
+    });
+    standings
+}
+
+pub fn to_csv(standings: &[Standing]) -> String {
+    let optional = |value: Option<f64>| value.map_or(String::new(), |value| format!("{value:.2}"));
+
+    let mut csv = String::from("rank,name,strategy,matches,wins,win_rate,timeouts,mean_moves,median_moves\n");
+    for (rank, standing) in standings.iter().enumerate() {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{:.3},{},{},{}\n",
+            rank + 1,
+            standing.name,
+            standing.strategy,
+            standing.matches,
+            standing.wins,
+            standing.win_rate,
+            standing.timeouts,
+            optional(standing.mean_moves),
+            optional(standing.median_moves)
+        ));
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn team(name: &str, strategy: &str) -> TeamConfig {
+        TeamConfig {
+            name: name.to_string(),
+            strategy: strategy.to_string(),
+        }
+    }
+
+    fn config(teams: Vec<TeamConfig>) -> TournamentConfig {
+        TournamentConfig {
+            teams,
+            mazes: vec![
+                MazeConfig { width: 6, height: 6, seed: 1 },
+                MazeConfig { width: 8, height: 5, seed: 2 },
+            ],
+            players_per_team: 2,
+            max_turns: 200,
+            challenge_every: Some(5),
+        }
+    }
+
+    #[test]
+    fn test_fixtures() {
+        assert_eq!(fixtures(1), vec![vec![0]]);
+        assert_eq!(fixtures(3).len(), 6);
+        assert!(fixtures(3).contains(&vec![2, 0]));
+    }
+
+    #[test]
+    fn test_parse_specs() {
+        assert_eq!(parse_teams("a=client, b=random").unwrap(), vec![team("a", "client"), team("b", "random")]);
+        assert!(parse_teams("a").is_err());
+
+        let mazes = parse_mazes("10x8:3,6x6:1-3").unwrap();
+        assert_eq!(mazes.len(), 4);
+        assert_eq!(mazes[0], MazeConfig { width: 10, height: 8, seed: 3 });
+        assert_eq!(mazes[3], MazeConfig { width: 6, height: 6, seed: 3 });
+        assert_eq!(parse_mazes("10:3"), Err(TournamentError::InvalidSpec(String::from("10:3"))));
+        for spec in ["0x5:1", "10x0:1", "10x10:5-1"] {
+            assert_eq!(parse_mazes(spec), Err(TournamentError::InvalidSpec(String::from(spec))));
+        }
+    }
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median(&[]), None);
+        assert_eq!(median(&[3, 5, 40]), Some(5.0));
+        assert_eq!(median(&[3, 5, 7, 40]), Some(6.0));
+    }
+
+    #[test]
+    fn test_round_robin_leaderboard() {
+        let config = config(vec![team("right", "right-hand"), team("left", "left-hand"), team("drunk", "random")]);
+        let results = run(&config).unwrap();
+        assert_eq!(results.len(), 2 * 6);
+
+        let standings = leaderboard(&config, &results);
+        assert_eq!(standings.len(), 3);
+        for standing in &standings {
+            assert_eq!(standing.matches, 2 * 4);
+            assert!(standing.wins <= standing.matches);
+        }
+        assert!(standings.windows(2).all(|pair| pair[0].win_rate >= pair[1].win_rate));
+
+        // Le tournoi est reproductible
+        assert_eq!(standings, leaderboard(&config, &run(&config).unwrap()));
+
+        let csv = to_csv(&standings);
+        assert_eq!(csv.lines().count(), 4);
+        assert!(csv.starts_with("rank,name,strategy,"));
+    }
+
+    #[test]
+    fn test_invalid_configs() {
+        assert_eq!(
+            run(&config(vec![team("fast", "teleport")])).unwrap_err(),
+            TournamentError::UnknownStrategy(String::from("teleport"))
+        );
+        assert_eq!(
+            run(&config(vec![team("twin", "random"), team("twin", "client")])).unwrap_err(),
+            TournamentError::DuplicateTeam(String::from("twin"))
+        );
+    }
+}
@@ -0,0 +1,47 @@
+use std::{env, path::Path, process};
+
+use shared::{recorder::read_recording, replay::replay};
+
+// Rejoue hors ligne un enregistrement fait avec `--record` et vérifie que le client
+// prendrait aujourd'hui exactement les mêmes décisions
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let Some(path) = args.get(1) else {
+        eprintln!("Usage: replay <enregistrement.jsonl>");
+        process::exit(2);
+    };
+
+    let messages = match read_recording(Path::new(path)) {
+        Ok(messages) => messages,
+        Err(err) => {
+            eprintln!("Impossible de lire {path}: {err}");
+            process::exit(2);
+        }
+    };
+
+    let report = replay(&messages);
+
+    println!(
+        "{} messages rejoués, {} actions comparées, {} messages ignorés",
+        report.received,
+        report.checked,
+        report.skipped.len()
+    );
+
+    for divergence in &report.divergences {
+        println!(
+            "Divergence #{} ({}): enregistré {} / rejoué {}",
+            divergence.index,
+            divergence.player,
+            divergence.recorded.as_ref().map_or(String::from("rien"), |message| message.to_string()),
+            divergence.replayed.as_ref().map_or(String::from("rien"), |message| message.to_string()),
+        );
+    }
+
+    if report.is_identical() {
+        println!("Le client reproduit la partie à l'identique");
+    } else {
+        println!("{} divergence(s)", report.divergences.len());
+        process::exit(1);
+    }
+}
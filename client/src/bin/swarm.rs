@@ -0,0 +1,84 @@
+use std::{
+    env, process,
+    sync::{Arc, Mutex},
+};
+
+use shared::{
+    async_functions::{connect, play, register_player, register_team, with_player},
+    enums::{RegisterTeamResult, SubscribePlayerResult},
+    functions::get_arg_value,
+    game_engine::{Direction, GameState, GlobalMap, Player},
+};
+use tokio::task::JoinSet;
+
+const SERVER_ADDRESS: &str = "localhost:8888";
+
+// Test de charge : `--teams` équipes jouent en même temps dans un seul processus,
+// chaque joueur est une tâche tokio et non un thread
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let address = get_arg_value(&args, "--address").unwrap_or(String::from(SERVER_ADDRESS));
+    let teams: usize = get_arg_value(&args, "--teams").and_then(|value| value.parse().ok()).unwrap_or(10);
+
+    let runtime = tokio::runtime::Runtime::new().expect("Impossible de démarrer le runtime tokio");
+    let turns = runtime.block_on(async {
+        let mut players = JoinSet::new();
+
+        for team in 0..teams {
+            let name = format!("swarm-{team}");
+            let (expected_players, token) = match register_team(&name, &address).await {
+                Ok(RegisterTeamResult::Ok { expected_players, registration_token }) => (expected_players, registration_token),
+                Ok(RegisterTeamResult::Err(err)) => {
+                    eprintln!("Équipe {name} refusée: {err:?}");
+                    continue;
+                }
+                Err(err) => {
+                    eprintln!("Impossible d'inscrire l'équipe {name}: {err}");
+                    process::exit(1);
+                }
+            };
+
+            // Comme dans le client, l'état de jeu et la carte sont partagés par l'équipe
+            let game_state = Arc::new(Mutex::new(GameState::new()));
+            let map = Arc::new(Mutex::new(GlobalMap::new()));
+
+            for i in 0..expected_players {
+                let player_name = format!("{name}-{i}");
+                let (address, token, game_state, map) = (address.clone(), token.clone(), game_state.clone(), map.clone());
+
+                players.spawn(with_player(player_name.clone(), async move {
+                    let mut stream = connect(&address).await.ok()?;
+                    match register_player(&player_name, &token, &mut stream).await.ok()? {
+                        SubscribePlayerResult::Ok => (),
+                        SubscribePlayerResult::Err(err) => {
+                            eprintln!("Joueur {player_name} refusé: {err:?}");
+                            return None;
+                        }
+                    }
+
+                    let mut player = Player {
+                        name: player_name,
+                        position: (0, 0),
+                        secret: None,
+                        direction: Direction::Front,
+                    };
+                    let mut turns = 0u64;
+                    while play(&mut player, &mut stream, &game_state, &map).await.is_ok() {
+                        turns += 1;
+                    }
+                    Some(turns)
+                }));
+            }
+        }
+
+        players.join_all().await
+    });
+
+    let finished: Vec<u64> = turns.iter().flatten().copied().collect();
+    println!(
+        "{} joueurs lancés, {} ont joué jusqu'à la fin de la connexion, {} tours au total",
+        turns.len(),
+        finished.len(),
+        finished.iter().sum::<u64>()
+    );
+}
@@ -0,0 +1,149 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    io::{self, Write},
+};
+
+use shared::{
+    enums::{Action, ActionError, Challenge, Message},
+    functions::Turn,
+    game_engine::{Direction, GlobalMap, Player},
+};
+
+const RECENT_MESSAGES: usize = 8;
+const MESSAGE_WIDTH: usize = 70;
+
+struct PlayerPanel {
+    heading: Direction,
+    plan: String, // Dernière action décidée par le joueur
+}
+
+// Tableau de bord de l'équipe, rafraîchi à chaque vue radar
+pub struct Dashboard {
+    team: String,
+    players: BTreeMap<String, PlayerPanel>,
+    recent: VecDeque<String>,
+    challenge: String,
+    challenge_failures: u64,
+}
+
+impl Dashboard {
+    pub fn new(team: &str) -> Self {
+        Self {
+            team: team.to_string(),
+            players: BTreeMap::new(),
+            recent: VecDeque::new(),
+            challenge: String::from("aucun"),
+            challenge_failures: 0,
+        }
+    }
+
+    pub fn record_turn(&mut self, player: &Player, turn: &Turn) {
+        self.push_recent(format!("<- {} {:?}", player.name, turn.received));
+        for message in &turn.sent {
+            self.push_recent(format!("-> {} {:?}", player.name, message));
+        }
+
+        let answer = turn.sent.iter().find_map(|message| match message {
+            Message::Action(Action::SolveChallenge { answer }) => Some(answer.clone()),
+            _ => None,
+        });
+
+        match &turn.received {
+            Message::Challenge(Challenge::SecretSumModulo(modulo)) => {
+                self.challenge = format!(
+                    "SecretSumModulo({modulo}) pour {}, réponse {}",
+                    player.name,
+                    answer.unwrap_or_default()
+                );
+            }
+            Message::ActionError(ActionError::InvalidChallengeSolution) => {
+                self.challenge_failures += 1;
+                self.challenge = format!("réponse refusée, nouvelle réponse {}", answer.unwrap_or_default());
+            }
+            // Une vue radar après un challenge signifie qu'il est résolu
+            Message::RadarView(_) if self.challenge != "aucun" && !self.challenge.ends_with("(résolu)") => {
+                self.challenge.push_str(" (résolu)");
+            }
+            _ => (),
+        }
+
+        let panel = self.players.entry(player.name.clone()).or_insert(PlayerPanel {
+            heading: player.direction,
+            plan: String::from("en attente"),
+        });
+        panel.heading = player.direction;
+        if let Some(message) = turn.sent.last() {
+            panel.plan = format!("{:?}", message);
+        }
+    }
+
+    fn push_recent(&mut self, mut line: String) {
+        if line.chars().count() > MESSAGE_WIDTH {
+            line = line.chars().take(MESSAGE_WIDTH - 3).collect::<String>() + "...";
+        }
+        self.recent.push_back(line);
+        while self.recent.len() > RECENT_MESSAGES {
+            self.recent.pop_front();
+        }
+    }
+
+    pub fn render(&self, map: &GlobalMap) -> String {
+        let mut frame = format!("=== Équipe {} ===\n\n", self.team);
+        frame.push_str(&map.render());
+        frame.push_str("@ joueur  P allié  G objectif  H indice  M monstre  ? inconnu\n\n");
+
+        frame.push_str("Joueurs:\n");
+        for (name, panel) in &self.players {
+            frame.push_str(&format!("  {name:<10} cap: {:<6} plan: {}\n", format!("{:?}", panel.heading), panel.plan));
+        }
+
+        frame.push_str(&format!(
+            "\nChallenge: {} (échecs: {})\n\nDerniers messages:\n",
+            self.challenge, self.challenge_failures
+        ));
+        for line in &self.recent {
+            frame.push_str(&format!("  {line}\n"));
+        }
+        frame
+    }
+}
+
+// Efface le terminal et affiche le tableau de bord sur la sortie d'erreur,
+// la sortie standard reste disponible pour les logs (`> client.log`)
+pub fn draw(frame: &str) {
+    let mut stderr = io::stderr().lock();
+    let _ = write!(stderr, "\x1b[2J\x1b[H{frame}");
+    let _ = stderr.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use shared::enums::Hint;
+
+    use super::*;
+
+    #[test]
+    fn test_render_tracks_players_and_challenge() {
+        let mut dashboard = Dashboard::new("team");
+        let player = Player {
+            name: String::from("Player-0"),
+            position: (0, 0),
+            secret: None,
+            direction: Direction::Right,
+        };
+
+        dashboard.record_turn(&player, &Turn {
+            received: Message::Challenge(Challenge::SecretSumModulo(7)),
+            sent: vec![Message::Action(Action::SolveChallenge { answer: String::from("3") })],
+        });
+        dashboard.record_turn(&player, &Turn {
+            received: Message::Hint(Hint::Secret(4)),
+            sent: Vec::new(),
+        });
+
+        let frame = dashboard.render(&GlobalMap::new());
+        assert!(frame.contains("Player-0   cap: Right  plan: Action(SolveChallenge { answer: \"3\" })"));
+        assert!(frame.contains("Challenge: SecretSumModulo(7) pour Player-0, réponse 3 (échecs: 0)"));
+        assert!(frame.contains("<- Player-0 Hint(Secret(4))"));
+    }
+}
@@ -1,93 +1,195 @@
 use std::{
-    collections::{HashMap, HashSet}, env, net::TcpStream, sync::{Arc, Mutex}, thread
+    env, fs,
+    io::{self, Write},
+    net::TcpStream,
+    path::Path,
+    process,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
 };
 
+use dashboard::{draw, Dashboard};
 use shared::{
-    enums::RegisterTeamResult,
-    functions::{connect, play, register_player, register_team},
+    enums::{Message, RegisterTeamResult},
+    functions::{get_arg_value, play, register_player, register_team},
     game_engine::{Direction, GameState, GlobalMap, Player},
+    recorder,
+    shutdown::{Shutdown, ShutdownReason},
 };
 
+mod dashboard;
+mod viewer;
+
 const SERVER_ADDRESS: &str = "localhost:8778";
 const TEST_SERVER_ADDRESS: &str= "localhost:8888";
+const SPECTATOR_ADDRESS: &str = "localhost:8889";
+const DUMP_FILE: &str = "deadruster_dump.txt";
+const TEAM_NAME: &str = "deadRuster0X256";
 
 pub fn get_mode() -> String {
     let args: Vec<String> = env::args().collect();
-    let mode = args[1].clone();
-    mode
+    args[1].clone()
+}
+
+// Écrit la carte explorée et les statistiques de chaque joueur à la fin de la partie
+fn write_dump(reason: &ShutdownReason, game_state: &GameState, map: &GlobalMap) -> io::Result<()> {
+    let mut dump = format!("=== Fin de partie: {:?} ===\n\n=== Carte ===\n", reason);
+    dump.push_str(&map.render());
+
+    dump.push_str("\n=== Statistiques ===\n");
+    let mut names: Vec<&String> = game_state.stats.keys().collect();
+    names.sort();
+    for name in names {
+        let stats = &game_state.stats[name];
+        dump.push_str(&format!(
+            "{name}: moves={} wall_collisions={} challenges_answered={} challenge_failures={}\n",
+            stats.moves, stats.wall_collisions, stats.challenges_answered, stats.challenge_failures
+        ));
+    }
+
+    fs::write(DUMP_FILE, dump)
 }
 
 fn main() {
-    let team_token: String;
-    let max_players: u8;
     let mut server_address = TEST_SERVER_ADDRESS;
     let mode = get_mode();
-    
+
     println!("Mode is {mode}");
 
+    // Regarder le match du serveur de test: `viewer [--address <adresse>]`
+    if mode == "viewer" {
+        let args: Vec<String> = env::args().collect();
+        let address = get_arg_value(&args, "--address").unwrap_or(String::from(SPECTATOR_ADDRESS));
+        if let Err(err) = viewer::run(&address) {
+            eprintln!("Spectateur déconnecté de {address}: {err}");
+            process::exit(1);
+        }
+        return;
+    }
+
     if mode == "live" {
         server_address  = SERVER_ADDRESS;
     }
 
-    // Enregistrer une équipe
-    let register_message: RegisterTeamResult = register_team("deadRuster0X256", server_address);
+    // Enregistrer tous les messages échangés si demandé: `--record <fichier>`
+    let args: Vec<String> = env::args().collect();
+    if let Some(record_path) = get_arg_value(&args, "--record") {
+        recorder::start_recording(Path::new(&record_path)).expect("Impossible de créer le fichier d'enregistrement");
+        println!("Enregistrement des messages dans {record_path}");
+    }
+
+    // Signal d'arrêt partagé : Ctrl-C, fin de partie ou erreur fatale. Installé avant
+    // l'inscription de l'équipe, qui peut bloquer si le serveur ne répond pas
+    let shutdown = Arc::new(Shutdown::new());
+    let registered = Arc::new(AtomicBool::new(false));
+    let shutdown_handler = Arc::clone(&shutdown);
+    let registered_handler = Arc::clone(&registered);
+    ctrlc::set_handler(move || {
+        println!("\n ==== Interruption demandée ===\n");
+        shutdown_handler.trigger(ShutdownReason::Interrupted);
+        // Aucune connexion à fermer pendant l'inscription : rien à écrire, on quitte directement
+        if !registered_handler.load(Ordering::SeqCst) {
+            process::exit(ShutdownReason::Interrupted.exit_code());
+        }
+    })
+    .expect("Impossible d'installer le gestionnaire Ctrl-C");
+
+    // Enregistrer une équipe, `--team <nom>` pour lancer plusieurs équipes sur le même serveur
+    let team_name = get_arg_value(&args, "--team").unwrap_or(String::from(TEAM_NAME));
+    recorder::set_player(&team_name);
+    // Une équipe refusée, ou un serveur injoignable, ne lance aucun joueur
+    let failed = |cause: String| {
+        eprintln!("Inscription de l'équipe {team_name} impossible: {cause}");
+        shutdown.trigger(ShutdownReason::Fatal(format!("Inscription de l'équipe impossible: {cause}")));
+        (String::new(), 0)
+    };
 
     // Déconstruire le message du server
-    match register_message {
-        RegisterTeamResult::Ok {
+    let (team_token, max_players) = match register_team(&team_name, server_address) {
+        Ok(RegisterTeamResult::Ok {
             expected_players,
             registration_token,
-        } => {
+        }) => {
             println!("Expected player: {:?}", &expected_players);
-            team_token = registration_token;
-            max_players = expected_players;
+            println!("Token: {:?}\n", &registration_token);
+            (registration_token, expected_players)
         }
 
-        RegisterTeamResult::Err(err) => {
-            panic!("{:?}\n", err);
-        }
-    }
-
-    println!("Token: {:?}\n", &team_token);
+        Ok(RegisterTeamResult::Err(err)) => failed(format!("{err:?}")),
+        Err(err) => failed(err.to_string()),
+    };
+    registered.store(true, Ordering::SeqCst);
 
     // Initialiser l'état du jeu
-    let game_state = Arc::new(Mutex::new(GameState {
-        team_secrets: HashMap::new(),
-        modulo: 0,
-    }));
+    let game_state = Arc::new(Mutex::new(GameState::new()));
 
     let mut threads: Vec<_> = Vec::new();
     let map = Arc::new(Mutex::new(GlobalMap::new()));
 
+    // Tableau de bord dans le terminal si demandé: `--tui`
+    let dashboard = args
+        .iter()
+        .any(|arg| arg == "--tui")
+        .then(|| Arc::new(Mutex::new(Dashboard::new(&team_name))));
 
     // Enregister des joueurs et lancer la partie
     for i in 0..max_players {
         let team_token_clone = team_token.clone();
         let mut game_state_clone = Arc::clone(&game_state);
         let mut map_clone = Arc::clone(&map);
+        let shutdown_clone = Arc::clone(&shutdown);
+        let dashboard_clone = dashboard.clone();
 
         threads.push(thread::spawn(move || {
-            let mut stream: TcpStream = connect(server_address);
-
-            let can_play = register_player(
-                format!("Player-{}", &i).as_str(),
-                &team_token_clone,
-                &mut stream,
-            );
-
-            if can_play {
-
-                let mut player = Player {
-                    name: format!("Player-{}", &i),
-                    position: (0, 0),
-                    secret: Some(0),
-                    direction: Direction::Front
-                };
-
-                println!("\n ==== La partie a commencé ===\n");
-
-                loop {
-                    play(&mut player, &mut stream, &mut game_state_clone, &mut map_clone);
+            recorder::set_player(&format!("Player-{}", &i));
+            let registration = TcpStream::connect(server_address).and_then(|mut stream| {
+                shutdown_clone.register_stream(&stream);
+                let can_play = register_player(format!("Player-{}", &i).as_str(), &team_token_clone, &mut stream)?;
+                Ok((stream, can_play))
+            });
+
+            // Un joueur refusé empêche l'équipe de jouer au complet
+            let mut stream = match registration {
+                Ok((stream, true)) => stream,
+                Ok((_, false)) => {
+                    println!("\n ==== Player-{i} n'a pas pu s'inscrire ===\n");
+                    shutdown_clone.trigger(ShutdownReason::Fatal(format!("Inscription de Player-{i} refusée")));
+                    return;
+                }
+                Err(err) => {
+                    println!("\n ==== Inscription de Player-{i} interrompue: {err} ===\n");
+                    shutdown_clone.trigger(ShutdownReason::Fatal(format!("Inscription de Player-{i} impossible: {err}")));
+                    return;
+                }
+            };
+
+            let mut player = Player {
+                name: format!("Player-{}", &i),
+                position: (0, 0),
+                secret: Some(0),
+                direction: Direction::Front
+            };
+
+            println!("\n ==== La partie a commencé ===\n");
+
+            while !shutdown_clone.is_triggered() {
+                match play(&mut player, &mut stream, &mut game_state_clone, &mut map_clone) {
+                    Ok(turn) => {
+                        if let Some(dashboard) = &dashboard_clone {
+                            let mut dashboard = dashboard.lock().unwrap();
+                            dashboard.record_turn(&player, &turn);
+                            if let Message::RadarView(_) = turn.received {
+                                draw(&dashboard.render(&map_clone.lock().unwrap()));
+                            }
+                        }
+                    }
+                    Err(err) => {
+                        println!("\n ==== {} s'arrête: {} ===\n", player.name, err);
+                        shutdown_clone.trigger(ShutdownReason::from_io_error(&err));
+                    }
                 }
             }
         }));
@@ -95,7 +197,22 @@ fn main() {
 
     // Attendre que tous les threads se terminent
     for thread in threads {
-        thread.join().unwrap();
+        if thread.join().is_err() {
+            shutdown.trigger(ShutdownReason::Fatal(String::from("Un thread joueur a paniqué")));
+        }
     }
-}
 
+    let reason = shutdown.reason().unwrap_or(ShutdownReason::GameOver);
+
+    // Un thread qui a paniqué laisse les mutex empoisonnés, l'état reste lisible
+    let game_state = game_state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let map = map.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    match write_dump(&reason, &game_state, &map) {
+        Ok(()) => println!("Carte et statistiques écrites dans {DUMP_FILE}"),
+        Err(err) => eprintln!("Impossible d'écrire {DUMP_FILE}: {err}"),
+    }
+
+    println!("\n ==== Fin du client: {:?} ===\n", reason);
+    let _ = io::stdout().flush();
+    process::exit(reason.exit_code());
+}
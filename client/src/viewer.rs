@@ -0,0 +1,104 @@
+use std::{collections::VecDeque, io, net::TcpStream};
+
+use shared::{
+    functions::get_message,
+    maze::Maze,
+    spectator::{SpectatorMessage, WorldUpdate},
+};
+
+use crate::dashboard::draw;
+
+const RECENT_EVENTS: usize = 10;
+
+// Mode spectateur : affiche tous les joueurs du serveur de test dans le terminal, jusqu'à ce
+// que le serveur ferme la connexion
+pub fn run(address: &str) -> io::Result<()> {
+    let mut stream = TcpStream::connect(address)?;
+    let mut maze: Option<Maze> = None;
+    let mut recent = VecDeque::new();
+
+    loop {
+        match serde_json::from_str(&get_message(&mut stream)?)? {
+            SpectatorMessage::Maze(new_maze) => {
+                maze = Some(new_maze);
+                recent.clear();
+            }
+            SpectatorMessage::Tick(update) => {
+                let Some(maze) = &maze else {
+                    continue;
+                };
+                for event in &update.events {
+                    recent.push_back(update.describe(event));
+                }
+                while recent.len() > RECENT_EVENTS {
+                    recent.pop_front();
+                }
+                draw(&render(maze, &update, &recent));
+            }
+        }
+    }
+}
+
+pub fn render(maze: &Maze, update: &WorldUpdate, recent: &VecDeque<String>) -> String {
+    let mut frame = format!("=== Spectateur, tick {} ===\n\n", update.tick);
+    frame.push_str(&update.to_world(maze).render());
+    frame.push_str("chiffre: équipe  M monstre  E sortie  H indice  S départ\n\n");
+
+    for (id, team) in update.teams.iter().enumerate() {
+        frame.push_str(&format!("{id}: {team}\n"));
+        for player in update.players.iter().filter(|player| player.team == id) {
+            frame.push_str(&format!(
+                "  {:<10} ({}, {}) cap: {:?}",
+                player.name, player.position.x, player.position.y, player.heading
+            ));
+            if let Some(modulo) = player.challenge {
+                frame.push_str(&format!("  challenge {modulo}"));
+            }
+            if player.exited {
+                frame.push_str("  sorti");
+            }
+            frame.push('\n');
+        }
+    }
+    if !update.monsters.is_empty() {
+        frame.push_str(&format!("Monstres: {}\n", update.monsters.len()));
+    }
+
+    frame.push_str("\nÉvènements:\n");
+    for line in recent {
+        frame.push_str(&format!("  {line}\n"));
+    }
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+    use shared::{
+        referee::{GameEvent, Referee},
+        simulator::SimulationConfig,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_render_lists_teams_and_events() {
+        let mut referee = Referee::new(&SimulationConfig::default());
+        let blue = referee.add_team("blue");
+        referee.add_team("red");
+        let player = referee.add_player(blue, "Player-0");
+        referee.world.start_challenge(player, 12);
+
+        let world = &referee.world;
+        let update = WorldUpdate::new(7, world, vec![GameEvent::ChallengeStarted { player, modulo: 12 }]);
+        let recent = update.events.iter().map(|event| update.describe(event)).collect();
+        let frame = render(&world.maze, &update, &recent);
+
+        let start = world.maze.start;
+        assert!(frame.starts_with("=== Spectateur, tick 7 ==="));
+        assert!(frame.contains(&format!(
+            "0: blue\n  Player-0   ({}, {}) cap: North  challenge 12\n1: red\n",
+            start.x, start.y
+        )));
+        assert!(frame.contains("  Player-0 (blue): challenge SecretSumModulo(12)\n"));
+    }
+}
@@ -0,0 +1,20 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shared::base64::{decode, encode, STANDARD, URL_SAFE};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    // Aucun décodage ne doit paniquer, et tout texte accepté est l'unique encodage de ses octets
+    if let Ok(bytes) = decode(text) {
+        assert_eq!(encode(&bytes), text);
+    }
+    for engine in [STANDARD, URL_SAFE] {
+        if let Ok(bytes) = engine.decode(text) {
+            assert_eq!(engine.encode(&bytes), text);
+        }
+    }
+});
@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shared::radar_view::{decode_radarview, encode_radarview};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    // Une vue acceptée se ré-encode à l'identique
+    if let Ok(radar) = decode_radarview(text) {
+        assert_eq!(encode_radarview(&radar).unwrap(), text);
+        let _ = radar.render(shared::radar_view::RenderStyle::Unicode);
+    }
+});
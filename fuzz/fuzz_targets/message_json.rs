@@ -0,0 +1,17 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shared::enums::Message;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    // Un message accepté doit pouvoir être renvoyé tel quel
+    if let Ok(message) = serde_json::from_str::<Message>(text) {
+        let json = serde_json::to_string(&message).unwrap();
+        assert!(serde_json::from_str::<Message>(&json).is_ok());
+    }
+    let _ = Message::raw_radar_view(text);
+});
@@ -0,0 +1,122 @@
+use std::{
+    io,
+    sync::{Arc, Mutex},
+};
+
+use crate::{tournament::Tournament, Controller};
+
+const HELP: &str = "Commandes:
+  list                         équipes, jetons et joueurs (position, connexion)
+  kick <équipe> <joueur>       déconnecte un joueur
+  pause | resume               suspend ou reprend la partie
+  challenge <équipe> <joueur>  lance un challenge au prochain tour du joueur
+  maze                         affiche le labyrinthe (chiffre: équipe, M: monstre, E: sortie, H: indice, S: départ)
+  reset                        termine le match, les équipes doivent se réinscrire
+  help                         cette aide";
+
+// Console d'administration sur l'entrée standard du serveur de test
+pub fn run(controller: Arc<Mutex<Controller>>, tournament: Arc<Tournament>) {
+    println!("Console d'administration: `help` pour la liste des commandes");
+    for line in io::stdin().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        let output = execute(&line, &controller, &tournament);
+        if !output.is_empty() {
+            println!("{output}");
+        }
+    }
+}
+
+pub fn execute(line: &str, controller: &Mutex<Controller>, tournament: &Tournament) -> String {
+    let words: Vec<&str> = line.split_whitespace().collect();
+
+    match words.as_slice() {
+        [] => String::new(),
+        ["help"] => HELP.to_string(),
+        ["list"] => list(controller, tournament),
+        ["kick", team, player] => {
+            if tournament.kick(team, player) {
+                format!("{player} ({team}) déconnecté")
+            } else {
+                format!("Joueur inconnu: {player} ({team})")
+            }
+        }
+        ["pause"] => {
+            tournament.set_paused(true);
+            String::from("Partie en pause")
+        }
+        ["resume"] => {
+            tournament.set_paused(false);
+            String::from("Partie reprise")
+        }
+        ["challenge", team, player] => match tournament.force_challenge(team, player) {
+            Some(modulo) => format!("Challenge SecretSumModulo({modulo}) pour {player} ({team})"),
+            None => format!("Joueur inconnu: {player} ({team})"),
+        },
+        ["maze"] => tournament.render(),
+        ["reset"] => {
+            controller.lock().unwrap().reset();
+            tournament.reset();
+            String::from("Match réinitialisé, en attente des équipes")
+        }
+        _ => format!("Commande inconnue: {line}\n{HELP}"),
+    }
+}
+
+fn list(controller: &Mutex<Controller>, tournament: &Tournament) -> String {
+    let players = tournament.players();
+    let controller = controller.lock().unwrap();
+    let mut names: Vec<&String> = controller.teams.keys().collect();
+    names.sort();
+
+    let mut text = String::new();
+    for name in names {
+        let team = &controller.teams[name];
+        text.push_str(&format!("{} (jeton {})\n", team.name, team.token));
+        for player in players.iter().filter(|player| &player.team == name) {
+            text.push_str(&format!(
+                "  {}: ({}, {}) {:?}",
+                player.name, player.position.x, player.position.y, player.state
+            ));
+            if let Some(modulo) = player.challenge {
+                text.push_str(&format!(" challenge {modulo}"));
+            }
+            text.push('\n');
+        }
+    }
+    if text.is_empty() {
+        text.push_str("Aucune équipe inscrite\n");
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use shared::simulator::SimulationConfig;
+
+    use super::*;
+    use crate::Team;
+
+    #[test]
+    fn test_commands() {
+        let controller = Mutex::new(Controller::new(2, 3, None));
+        let tournament = Tournament::new(SimulationConfig::default(), 1);
+
+        assert_eq!(execute("list", &controller, &tournament), "Aucune équipe inscrite\n");
+        assert!(execute("maze", &controller, &tournament).contains('S'));
+        assert!(execute("kick team Player-0", &controller, &tournament).starts_with("Joueur inconnu"));
+        assert!(execute("challenge team Player-0", &controller, &tournament).starts_with("Joueur inconnu"));
+        assert!(execute("fly", &controller, &tournament).starts_with("Commande inconnue"));
+
+        controller.lock().unwrap().save_team(&Team {
+            players: Vec::new(),
+            name: String::from("team"),
+            token: String::from("TOKEN"),
+        });
+        assert_eq!(execute("list", &controller, &tournament), "team (jeton TOKEN)\n");
+
+        execute("reset", &controller, &tournament);
+        assert!(controller.lock().unwrap().teams.is_empty());
+    }
+}
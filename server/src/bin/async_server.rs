@@ -0,0 +1,36 @@
+use std::{env, process};
+
+use shared::{async_server::TestServer, functions::get_arg_value, simulator::SimulationConfig};
+
+const SERVER_ADDRESS: &str = "localhost:8888";
+
+// Serveur de test asynchrone : toutes les connexions sont servies par quelques threads,
+// pour les tests de charge et les tournois avec beaucoup de joueurs
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let address = get_arg_value(&args, "--address").unwrap_or(String::from(SERVER_ADDRESS));
+
+    let config = SimulationConfig::from_args(&args).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        process::exit(2);
+    });
+    // Au plus `--teams <nombre>` équipes dans le match
+    let teams = get_arg_value(&args, "--teams").map_or(1, |value| {
+        value.parse().unwrap_or_else(|_| {
+            eprintln!("Valeur invalide pour --teams: {value}");
+            process::exit(2);
+        })
+    });
+
+    let runtime = tokio::runtime::Runtime::new().expect("Impossible de démarrer le runtime tokio");
+    let result = runtime.block_on(async {
+        let server = TestServer::bind(&address, config, teams).await?;
+        println!("Serveur asynchrone en écoute sur {}", server.local_addr()?);
+        server.run().await
+    });
+
+    if let Err(err) = result {
+        eprintln!("Une erreur c'est produite lors du lancement du server: {err}");
+        process::exit(1);
+    }
+}
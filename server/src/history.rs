@@ -0,0 +1,320 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use shared::{
+    functions::get_arg_value,
+    referee::{PlayerReport, TeamReport},
+    simulator::SimulationConfig,
+};
+
+pub const RESULTS_DIR: &str = "results";
+
+// Une partie terminée, telle qu'enregistrée dans l'historique
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchRecord {
+    pub finished_at: u64,         // Secondes depuis l'epoch Unix
+    pub duration_ms: u64,         // Du départ de la partie à la fin du dernier joueur
+    pub config: SimulationConfig, // Graine et paramètres du labyrinthe
+    pub teams: Vec<TeamReport>,   // Dans l'ordre du classement
+    pub players: Vec<PlayerReport>,
+}
+
+impl MatchRecord {
+    pub fn winner(&self) -> Option<&str> {
+        let first = self.teams.first().filter(|report| report.moves_to_exit.is_some())?;
+        let tied = self.teams[1..]
+            .iter()
+            .any(|report| report.moves_to_exit == first.moves_to_exit);
+        (!tied).then_some(first.name.as_str())
+    }
+}
+
+pub fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs())
+}
+
+// Historique des parties : un fichier JSON par partie dans un dossier
+pub struct ResultStore {
+    dir: PathBuf,
+}
+
+impl ResultStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    // Écrit la partie dans un nouveau fichier, sans jamais écraser une partie précédente
+    pub fn save(&self, record: &MatchRecord) -> io::Result<PathBuf> {
+        fs::create_dir_all(&self.dir)?;
+        let json = serde_json::to_string_pretty(record)?;
+
+        let mut attempt = 0;
+        loop {
+            let path = self.dir.join(format!("match-{}-{attempt}.json", record.finished_at));
+            match fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    file.write_all(json.as_bytes())?;
+                    return Ok(path);
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => attempt += 1,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    // Toutes les parties enregistrées, de la plus ancienne à la plus récente. Les fichiers
+    // illisibles sont ignorés
+    pub fn load(&self) -> io::Result<Vec<MatchRecord>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|extension| extension == "json"))
+            .collect();
+        paths.sort();
+
+        let mut records = Vec::new();
+        for path in paths {
+            match fs::read_to_string(&path).map(|text| serde_json::from_str::<MatchRecord>(&text)) {
+                Ok(Ok(record)) => records.push(record),
+                Ok(Err(e)) => println!("Fichier ignoré {}: {e}", path.display()),
+                Err(e) => println!("Fichier ignoré {}: {e}", path.display()),
+            }
+        }
+        records.sort_by_key(|record| record.finished_at);
+        Ok(records)
+    }
+}
+
+// Résultats cumulés d'une équipe sur plusieurs parties
+#[derive(Debug, Clone, PartialEq)]
+pub struct TeamHistory {
+    pub name: String,
+    pub matches: u64,
+    pub wins: u64,
+    pub exits: u64,
+    pub mean_moves_to_exit: Option<f64>, // Sur les parties où l'équipe est sortie
+    pub mean_collisions: f64,            // Murs et adversaires, par partie
+    pub challenges: u64,
+    pub challenge_failures: u64,
+    pub monster_catches: u64,
+}
+
+// Classement sur l'historique : meilleur taux de sortie, puis moins de déplacements en moyenne
+pub fn summarize(records: &[MatchRecord]) -> Vec<TeamHistory> {
+    let mut reports: BTreeMap<&str, Vec<(&TeamReport, bool)>> = BTreeMap::new();
+    for record in records {
+        let winner = record.winner();
+        for report in &record.teams {
+            let won = winner == Some(report.name.as_str());
+            reports.entry(report.name.as_str()).or_default().push((report, won));
+        }
+    }
+
+    let mut history: Vec<TeamHistory> = reports
+        .into_iter()
+        .map(|(name, reports)| {
+            let exits: Vec<u64> = reports.iter().filter_map(|(report, _)| report.moves_to_exit).collect();
+            let sum = |field: fn(&TeamReport) -> u64| reports.iter().map(|(report, _)| field(report)).sum::<u64>();
+            let matches = reports.len() as u64;
+
+            TeamHistory {
+                name: name.to_string(),
+                matches,
+                wins: reports.iter().filter(|(_, won)| *won).count() as u64,
+                exits: exits.len() as u64,
+                mean_moves_to_exit: (!exits.is_empty())
+                    .then(|| exits.iter().sum::<u64>() as f64 / exits.len() as f64),
+                mean_collisions: sum(|report| report.wall_collisions + report.opponent_collisions) as f64
+                    / matches as f64,
+                challenges: sum(|report| report.challenges),
+                challenge_failures: sum(|report| report.challenge_failures),
+                monster_catches: sum(|report| report.monster_catches),
+            }
+        })
+        .collect();
+
+    let exit_rate = |team: &TeamHistory| team.exits as f64 / team.matches as f64;
+    history.sort_by(|a, b| {
+        exit_rate(b).total_cmp(&exit_rate(a)).then_with(|| {
+            a.mean_moves_to_exit
+                .unwrap_or(f64::INFINITY)
+                .total_cmp(&b.mean_moves_to_exit.unwrap_or(f64::INFINITY))
+        })
+    });
+    history
+}
+
+// Date UTC `AAAA-MM-JJ HH:MM` d'un horodatage Unix
+pub fn format_date(timestamp: u64) -> String {
+    let (days, seconds) = ((timestamp / 86_400) as i64, timestamp % 86_400);
+
+    // Jours depuis le 1970-01-01 vers le calendrier grégorien (algorithme de Howard Hinnant)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02} {:02}:{:02}", seconds / 3600, seconds % 3600 / 60)
+}
+
+// Sous-commande `history` : `[--results <dossier>] [--last <parties>] [--team <équipe>]`
+pub fn query(args: &[String]) -> io::Result<String> {
+    let store = ResultStore::new(get_arg_value(args, "--results").unwrap_or(String::from(RESULTS_DIR)));
+    let mut records = store.load()?;
+    if let Some(last) = get_arg_value(args, "--last").and_then(|last| last.parse::<usize>().ok()) {
+        records.drain(..records.len().saturating_sub(last));
+    }
+    let team = get_arg_value(args, "--team");
+    if let Some(team) = &team {
+        records.retain(|record| record.teams.iter().any(|report| &report.name == team));
+    }
+
+    if records.is_empty() {
+        return Ok(String::from("Aucune partie enregistrée\n"));
+    }
+
+    let mut text = format!(
+        "{} partie(s), du {} au {}\n\n",
+        records.len(),
+        format_date(records[0].finished_at),
+        format_date(records[records.len() - 1].finished_at)
+    );
+    text.push_str("équipe               parties victoires sorties  dépl. moyens  collisions/partie  challenges (échecs)  monstres\n");
+    for history in summarize(&records) {
+        text.push_str(&format!(
+            "{:<20} {:>7} {:>9} {:>7}  {:>12}  {:>17.1}  {:>10} ({:>6})  {:>8}\n",
+            history.name,
+            history.matches,
+            history.wins,
+            history.exits,
+            history.mean_moves_to_exit.map_or(String::from("-"), |moves| format!("{moves:.1}")),
+            history.mean_collisions,
+            history.challenges,
+            history.challenge_failures,
+            history.monster_catches
+        ));
+    }
+
+    // Évolution d'une équipe, partie par partie
+    if let Some(team) = &team {
+        text.push_str(&format!("\nParties de {team}:\n"));
+        for record in &records {
+            let report = record.teams.iter().find(|report| &report.name == team).expect("filtered above");
+            let exit = match report.moves_to_exit {
+                Some(moves) => format!("sortie en {moves} déplacements"),
+                None => format!("pas de sortie ({} déplacements)", report.moves),
+            };
+            text.push_str(&format!(
+                "  {} {}x{} graine {} ({} ms): {exit}{}\n",
+                format_date(record.finished_at),
+                record.config.width,
+                record.config.height,
+                record.config.seed,
+                record.duration_ms,
+                if record.winner() == Some(team.as_str()) { ", victoire" } else { "" }
+            ));
+        }
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    fn record(finished_at: u64, teams: &[(&str, Option<u64>)]) -> MatchRecord {
+        MatchRecord {
+            finished_at,
+            duration_ms: 1500,
+            config: SimulationConfig::default(),
+            teams: teams
+                .iter()
+                .map(|&(name, moves_to_exit)| TeamReport {
+                    name: name.to_string(),
+                    moves: moves_to_exit.unwrap_or(50),
+                    moves_to_exit,
+                    wall_collisions: 2,
+                    ..TeamReport::default()
+                })
+                .collect(),
+            players: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_winner_and_summary() {
+        assert_eq!(record(0, &[("a", Some(10)), ("b", None)]).winner(), Some("a"));
+        assert_eq!(record(0, &[("a", Some(10)), ("b", Some(10))]).winner(), None);
+        assert_eq!(record(0, &[("a", None), ("b", None)]).winner(), None);
+
+        let records = [
+            record(1, &[("slow", Some(40)), ("lost", None)]),
+            record(2, &[("fast", Some(12)), ("slow", Some(20))]),
+        ];
+        let history = summarize(&records);
+        let names: Vec<&str> = history.iter().map(|team| team.name.as_str()).collect();
+        assert_eq!(names, ["fast", "slow", "lost"]);
+        assert_eq!(history[1].matches, 2);
+        assert_eq!(history[1].wins, 1);
+        assert_eq!(history[1].mean_moves_to_exit, Some(30.0));
+        assert_eq!(history[1].mean_collisions, 2.0);
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date(0), "1970-01-01 00:00");
+        assert_eq!(format_date(951_782_400), "2000-02-29 00:00");
+        assert_eq!(format_date(1_792_367_312), "2026-10-18 23:48");
+    }
+
+    #[test]
+    fn test_store_and_query() {
+        let dir = env::temp_dir().join(format!("history-test-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let store = ResultStore::new(&dir);
+        assert!(store.load().unwrap().is_empty());
+
+        // Deux parties terminées dans la même seconde ne s'écrasent pas
+        let first = store.save(&record(100, &[("blue", Some(8))])).unwrap();
+        let second = store.save(&record(100, &[("blue", None), ("red", Some(30))])).unwrap();
+        assert_ne!(first, second);
+        store.save(&record(50, &[("red", None)])).unwrap();
+        fs::write(dir.join("broken.json"), "{").unwrap();
+
+        let records = store.load().unwrap();
+        assert_eq!(records.iter().map(|record| record.finished_at).collect::<Vec<_>>(), [50, 100, 100]);
+
+        let args = |extra: &[&str]| -> Vec<String> {
+            ["server", "history", "--results", dir.to_str().unwrap()]
+                .iter()
+                .chain(extra)
+                .map(|arg| arg.to_string())
+                .collect()
+        };
+        let text = query(&args(&["--team", "blue"])).unwrap();
+        assert!(text.starts_with("2 partie(s), du 1970-01-01 00:01 au 1970-01-01 00:01\n"), "{text}");
+        assert!(text.contains("  1970-01-01 00:01 10x10 graine 0 (1500 ms): sortie en 8 déplacements, victoire\n"), "{text}");
+        assert!(query(&args(&["--last", "1"])).unwrap().starts_with("1 partie(s)"));
+
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(query(&args(&[])).unwrap(), "Aucune partie enregistrée\n");
+    }
+}
@@ -1,14 +1,24 @@
-use rand::{distr::Alphanumeric, rng, Rng};
 use serde::{Deserialize, Serialize};
 use shared::{
-    enums::{Action, Message, RegisterTeamResult, RegistrationError, SubscribePlayerResult}, functions::{get_message, send_message}, game_engine::{Direction, Player}, structs::SubscribePlayer
+    enums::{Message, RegisterTeamResult, RegistrationError, SubscribePlayerResult}, functions::{get_arg_value, get_message, send_message}, game_engine::{Direction, Player}, recorder, referee::stream_seed, simulator::SimulationConfig, structs::SubscribePlayer, tokens::TokenRegistry
 };
 use std::{
     collections::HashMap,
+    env,
+    io,
     net::{TcpListener, TcpStream},
+    path::Path,
+    process,
     sync::{Arc, Mutex},
     thread,
+    time::Duration,
 };
+use history::ResultStore;
+use tournament::Tournament;
+
+mod admin;
+mod history;
+mod tournament;
 
 pub struct Request;
 
@@ -35,10 +45,12 @@ impl Request {
 }
 
 const SERVER_PORT: &str = "localhost:8888";
+const SPECTATOR_PORT: &str = "localhost:8889";
 
 pub struct Controller {
     pub teams: HashMap<String, Team>,
     pub expected_players: u64,
+    tokens: TokenRegistry<String>, // Jeton d'inscription -> nom de l'équipe
 }
 
 pub struct Services;
@@ -54,7 +66,9 @@ impl Services {
             serde_json::to_string(&Message::RegisterTeamResult(register_result))
         {
             // Envoyer le message
-            send_message(stream, &register_team_string);
+            if let Err(e) = send_message(stream, &register_team_string) {
+                print!("Impossible d'envoyer le résultat d'enregistrement: {e}");
+            }
         }
     }
 
@@ -68,30 +82,60 @@ impl Services {
             serde_json::to_string(&Message::SubscribePlayerResult(subscribe_player_result))
         {
             // Envoyer le message
-            send_message(stream, &subscribe_player_string);
+            if let Err(e) = send_message(stream, &subscribe_player_string) {
+                print!("Impossible d'envoyer le résultat d'inscription: {e}");
+            }
         }
     }
 }
 
 impl Controller {
+    // Jetons tirés de `seed` pour rejouer une partie à l'identique, sinon d'une source sûre
+    pub fn new(expected_teams: usize, expected_players: u64, seed: Option<u64>) -> Self {
+        Self {
+            teams: HashMap::new(),
+            expected_players,
+            tokens: TokenRegistry::new(seed.map(|seed| stream_seed(seed, "tokens")), expected_teams),
+        }
+    }
+
     pub fn save_team(&mut self, team: &Team) {
+        self.tokens.insert(team.token.clone(), team.name.clone());
         self.teams.insert(team.clone().name, team.clone());
     }
 
+    // Ajoute le joueur à l'équipe de son jeton
     pub fn save_player(&mut self, player: &SubscribePlayer) {
-        for (_, team) in self.teams.iter_mut() {
-            if team.token == player.registration_token {
-                team.players.push(Player {
-                    position: (0, 0),
-                    name: player.name.clone(),
-                    secret: None,
-                    direction: Direction::Front
-                });
-            }
+        let Some(team) = self
+            .tokens
+            .get(&player.registration_token)
+            .and_then(|name| self.teams.get_mut(name))
+        else {
+            return;
+        };
+        team.players.push(Player {
+            position: (0, 0),
+            name: player.name.clone(),
+            secret: None,
+            direction: Direction::Front
+        });
+    }
+
+    // Fin de la partie : les jetons ne permettent plus d'inscrire de joueur et aucune équipe ne
+    // peut s'inscrire avant la réinitialisation du match
+    pub fn invalidate_tokens(&mut self) {
+        if self.tokens.close() {
+            println!("Jetons d'inscription invalidés");
         }
     }
 
-    pub fn register_team(&mut self, team: Team) -> RegisterTeamResult {
+    // Nouveau match : les équipes doivent se réinscrire
+    pub fn reset(&mut self) {
+        self.teams.clear();
+        self.tokens.reset();
+    }
+
+    pub fn register_team(&mut self, mut team: Team) -> RegisterTeamResult {
         // Vérifier si le nom de l'équipe n'est pas vide => InvalidName
         if team.name.len() < 3 {
             return RegisterTeamResult::Err(RegistrationError::InvalidName);
@@ -102,12 +146,16 @@ impl Controller {
             return RegisterTeamResult::Err(RegistrationError::AlreadyRegistered);
         }
 
-        // Sauvegarder l'équipe
-        self.save_team(&team);
+        // Nouveau jeton, sauf si le match est terminé ou complet => TooManyPlayers
+        team.token = match self.tokens.register(team.name.clone()) {
+            Ok(token) => token,
+            Err(error) => return RegisterTeamResult::Err(error),
+        };
+        self.teams.insert(team.name.clone(), team.clone());
 
         // Retourner le resultat de l'enregistrement
         RegisterTeamResult::Ok {
-            expected_players: 3,
+            expected_players: self.expected_players as u8,
             registration_token: team.token,
         }
     }
@@ -116,18 +164,12 @@ impl Controller {
         &mut self,
         player_to_subscribe: &SubscribePlayer,
     ) -> SubscribePlayerResult {
-        let mut player_team: Option<&Team> = None;
-
         // Trouver l'équipe avec le token correspondant
-        for (_, team) in self.teams.iter_mut() {
-            if team.token == player_to_subscribe.registration_token {
-                player_team = Some(team);
-                break; // On a trouvé l'équipe, on peut sortir de la boucle
-            }
-        }
-
-        // Si aucune équipe ne correspond, retourner une erreur
-        let player_team = match player_team {
+        let player_team = match self
+            .tokens
+            .get(&player_to_subscribe.registration_token)
+            .and_then(|name| self.teams.get(name))
+        {
             Some(team) => team,
             None => return SubscribePlayerResult::Err(RegistrationError::InvalidRegistrationToken),
         };
@@ -153,31 +195,9 @@ impl Controller {
         SubscribePlayerResult::Ok
     }
 
-    pub fn start_game(&mut self, mut stream: &mut TcpStream) {
-        print!("Start Game");
-
-        // Liste définié des vues radars à envoyé
-        let radarview_sample = "ieysGjGO8papd/a";
-
-        //if count == 3 {break};
-        // Envoyer un radarview
-        let radar_string =
-            Request::to_serde_string(Message::RadarView(String::from(radarview_sample))).unwrap();
-
-        print!("{}", &radar_string);
-
-        send_message(stream, &radar_string);
-
-        // Recevoir une action
-        let response: String = get_message(&mut stream);
-        let message: Message = Request::from_string(&response).unwrap();
-
-        if let Message::Action(action) = message {
-            match action {
-                Action::MoveTo(direction) => print!("MoveTo action: {:?}", direction),
-                Action::SolveChallenge { answer } => print!("Solve Challenge answer: {:?}", answer),
-            }
-        }
+    // Nom de l'équipe à laquelle appartient ce jeton d'inscription
+    pub fn team_name(&self, token: &str) -> Option<String> {
+        self.tokens.get(token).cloned()
     }
 }
 
@@ -188,85 +208,281 @@ pub struct Team {
     token: String,
 }
 
-pub fn gen_team_tokem() -> String {
-    let rand_string: String = rng()
-        .sample_iter(&Alphanumeric)
-        .take(12)
-        .map(char::from)
-        .collect();
-
-    print!("{rand_string}");
-    rand_string
-}
-
+// Traite la requête d'inscription d'une connexion. Retourne l'équipe et le nom du joueur
+// quand un joueur vient d'être inscrit : la connexion sert ensuite à sa partie
+// La requête est lue et décodée avant de verrouiller le contrôleur : un client lent ou une
+// requête invalide ne bloque pas les autres inscriptions
 pub fn handle_connection(
     stream: &mut TcpStream,
-    controller: &mut Controller,
+    controller: &Mutex<Controller>,
     services: &mut Services,
-) {
-    let request = get_message(stream);
-    let response: Message = Request::from_string(&request).unwrap();
+) -> io::Result<Option<(String, String)>> {
+    let request = get_message(stream)?;
+    let Some(response) = Request::from_string::<Message>(&request) else {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid registration request"));
+    };
 
     // Gérer les demandes d'enregistrement d'une équipe
     if let Message::RegisterTeam(register_team) = &response {
         print!("Demande d'enregistrement d'une équipe...");
+        recorder::set_player(&register_team.name);
 
-        // Créer la team
+        // Créer la team, le contrôleur lui attribue son jeton
         let team: Team = Team {
             name: register_team.name.clone(),
             players: Vec::new(),
-            token: gen_team_tokem(),
+            token: String::new(),
         };
 
         // Enregister la team
-        let register_team_result = controller.register_team(team);
+        let register_team_result = controller.lock().unwrap().register_team(team);
         services.register_team_service(register_team_result, stream);
     }
 
     // Gérer les demandes d'enregistrement des joueurs
     if let Message::SubscribePlayer(player_info) = &response {
         print!("Demande d'enregistrement d'un joueur...");
+        recorder::set_player(&player_info.name);
 
         // Enregistrer le joueur
-        let register_player_result = controller.register_player(&player_info);
+        let (register_player_result, team) = {
+            let mut controller = controller.lock().unwrap();
+            let result = controller.register_player(player_info);
+            (result, controller.team_name(&player_info.registration_token))
+        };
+        let subscribed = matches!(register_player_result, SubscribePlayerResult::Ok);
         services.subscribe_player_service(register_player_result, stream);
+
+        if subscribed {
+            return Ok(team.map(|team| (team, player_info.name.clone())));
+        }
+    }
+
+    Ok(None)
+}
+
+// Accepte les connexions : un thread par connexion, le contrôleur n'est verrouillé que pendant
+// l'inscription pour que les joueurs de toutes les équipes jouent en même temps
+pub fn serve(listener: TcpListener, controller: Arc<Mutex<Controller>>, tournament: Arc<Tournament>) {
+    for stream in listener.incoming() {
+        let controller_clone = Arc::clone(&controller);
+        let tournament_clone = Arc::clone(&tournament);
+
+        match stream {
+            Ok(mut stream) => {
+                // Gérer les connections/requêtes au server
+                thread::spawn(move || {
+                    // En attendant l'enregistrement, la connexion est identifiée par son adresse
+                    if let Ok(peer) = stream.peer_addr() {
+                        recorder::set_player(&peer.to_string());
+                    }
+                    let registered = match handle_connection(&mut stream, &controller_clone, &mut Services) {
+                        Ok(registered) => registered,
+                        Err(e) => {
+                            println!("Requête d'inscription refusée: {e}");
+                            None
+                        }
+                    };
+
+                    if let Some((team, player)) = registered {
+                        if let Err(e) = tournament_clone.play(&mut stream, &team, &player) {
+                            println!("Partie de {player} ({team}) interrompue: {e}");
+                        }
+                        if tournament_clone.is_finished() {
+                            controller_clone.lock().unwrap().invalidate_tokens();
+                        }
+                        print!("\n === Connexion fermée ===\n");
+                    }
+                });
+            }
+            Err(e) => print!("La connection au client à échoué: {:}", e),
+        }
     }
+}
 
-    // Démarrer le jeu si toute les équipes ont trois joueurs
-    print!("\n === La partie démarre ===\n");
-    loop {
-        controller.start_game(stream);
+// Accepte les spectateurs et leur envoie l'état du monde tous les `tick`
+pub fn serve_spectators(listener: TcpListener, tournament: Arc<Tournament>, tick: Duration) {
+    let ticker = Arc::clone(&tournament);
+    thread::spawn(move || loop {
+        thread::sleep(tick);
+        ticker.broadcast();
+    });
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                if let Ok(peer) = stream.peer_addr() {
+                    println!("Nouveau spectateur: {peer}");
+                }
+                tournament.add_spectator(stream);
+            }
+            Err(e) => print!("La connection du spectateur à échoué: {:}", e),
+        }
     }
 }
 
 fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    // Comparer les parties enregistrées: `history [--results <dossier>] [--last <parties>] [--team <équipe>]`
+    if args.get(1).is_some_and(|command| command == "history") {
+        match history::query(&args) {
+            Ok(text) => print!("{text}"),
+            Err(err) => {
+                eprintln!("Impossible de lire l'historique: {err}");
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
+    // Enregistrer tous les messages échangés si demandé: `--record <fichier>`
+    if let Some(record_path) = get_arg_value(&args, "--record") {
+        recorder::start_recording(Path::new(&record_path)).expect("Impossible de créer le fichier d'enregistrement");
+        println!("Enregistrement des messages dans {record_path}");
+    }
+
+    // Match à plusieurs équipes: `--teams <nombre>` équipes de `--players <nombre>` joueurs
+    let config = SimulationConfig::from_args(&args).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        process::exit(2);
+    });
+    let number = |flag: &str, default: u64| {
+        get_arg_value(&args, flag).map_or(default, |value| {
+            value.parse().unwrap_or_else(|_| {
+                eprintln!("Valeur invalide pour {flag}: {value}");
+                process::exit(2);
+            })
+        })
+    };
+    let teams = number("--teams", 1) as usize;
+    // Intervalle entre deux mises à jour des spectateurs: `--tick-ms <millisecondes>`
+    let tick = Duration::from_millis(number("--tick-ms", 200).max(1));
+
     // Initialiser le server
     let listener = TcpListener::bind(SERVER_PORT);
-    let controller = Arc::new(Mutex::new(Controller {
-        teams: HashMap::new(),
-        expected_players: 3,
-    }));
+    // Avec `--seed`, les jetons d'inscription sont aussi tirés de la graine pour rejouer la partie
+    println!("Graine de la partie: {} (`--seed <graine>` pour rejouer)", config.seed);
+    let token_seed = get_arg_value(&args, "--seed").map(|_| config.seed);
+    if token_seed.is_some() {
+        println!("Jetons d'inscription prévisibles (tirés de la graine)");
+    }
+    let controller = Arc::new(Mutex::new(Controller::new(teams, config.players_per_team as u64, token_seed)));
+    let mut tournament = Tournament::new(config, teams);
+
+    // Parties terminées enregistrées dans `--results <dossier>`, sauf avec `--no-results`
+    if !args.iter().any(|arg| arg == "--no-results") {
+        let dir = get_arg_value(&args, "--results").unwrap_or(String::from(history::RESULTS_DIR));
+        println!("Résultats des parties enregistrés dans {dir}");
+        tournament = tournament.with_results(ResultStore::new(dir));
+    }
+    let tournament = Arc::new(tournament);
 
     match listener {
         Ok(tcp_listener) => {
-            for stream in tcp_listener.incoming() {
-                let controller_clone = Arc::clone(&controller);
-
-                match stream {
-                    Ok(mut stream) => {
-                        // Gérer les connections/requêtes au server
-                        thread::spawn(move || {
-                            let mut controller = controller_clone.lock().unwrap();
-                            let mut services = Services;
-                            handle_connection(&mut stream, &mut controller, &mut services);
-                        });
-                    }
-                    Err(e) => print!("La connection au client à échoué: {:}", e),
+            println!("En attente de {teams} équipe(s) sur {SERVER_PORT}");
+
+            // Console d'administration sur l'entrée standard
+            let (admin_controller, admin_tournament) = (Arc::clone(&controller), Arc::clone(&tournament));
+            thread::spawn(move || admin::run(admin_controller, admin_tournament));
+
+            // Spectateurs sur un port dédié
+            match TcpListener::bind(SPECTATOR_PORT) {
+                Ok(spectator_listener) => {
+                    println!("Spectateurs sur {SPECTATOR_PORT}");
+                    let spectator_tournament = Arc::clone(&tournament);
+                    thread::spawn(move || serve_spectators(spectator_listener, spectator_tournament, tick));
                 }
+                Err(err) => println!("Pas de spectateurs, {SPECTATOR_PORT} indisponible: {err}"),
             }
+
+            serve(tcp_listener, controller, tournament);
         }
         Err(err) => {
             print!("Une erreur c'est produite lors du lancement du server: {err}")
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscribe(controller: &mut Controller, name: &str, token: &str) -> SubscribePlayerResult {
+        controller.register_player(&SubscribePlayer {
+            name: name.to_string(),
+            registration_token: token.to_string(),
+        })
+    }
+
+    fn try_register(controller: &mut Controller, name: &str) -> RegisterTeamResult {
+        controller.register_team(Team {
+            players: Vec::new(),
+            name: name.to_string(),
+            token: String::new(),
+        })
+    }
+
+    fn register(controller: &mut Controller, name: &str) -> String {
+        match try_register(controller, name) {
+            RegisterTeamResult::Ok { registration_token, .. } => registration_token,
+            RegisterTeamResult::Err(err) => panic!("{err:?}"),
+        }
+    }
+
+    #[test]
+    fn test_tokens_identify_one_team() {
+        let mut controller = Controller::new(2, 2, None);
+        let blue = register(&mut controller, "blue");
+        let red = register(&mut controller, "red");
+        assert_ne!(blue, red);
+        // Le match n'attend que deux équipes
+        assert!(matches!(
+            try_register(&mut controller, "green"),
+            RegisterTeamResult::Err(RegistrationError::TooManyPlayers)
+        ));
+        assert_eq!(controller.team_name(&red).as_deref(), Some("red"));
+
+        assert!(matches!(subscribe(&mut controller, "Player-0", &blue), SubscribePlayerResult::Ok));
+        assert!(matches!(
+            subscribe(&mut controller, "Player-0", &blue),
+            SubscribePlayerResult::Err(RegistrationError::AlreadyRegistered)
+        ));
+        assert!(matches!(
+            subscribe(&mut controller, "Player-0", "unknown"),
+            SubscribePlayerResult::Err(RegistrationError::InvalidRegistrationToken)
+        ));
+        assert_eq!(controller.teams["blue"].players.len(), 1);
+        assert!(controller.teams["red"].players.is_empty());
+
+        // Fin de partie : les jetons ne sont plus acceptés
+        controller.invalidate_tokens();
+        assert!(matches!(
+            subscribe(&mut controller, "Player-1", &blue),
+            SubscribePlayerResult::Err(RegistrationError::InvalidRegistrationToken)
+        ));
+        assert_eq!(controller.team_name(&blue), None);
+
+        // Ni nouvelle équipe avant la réinitialisation du match
+        controller.reset();
+        register(&mut controller, "green");
+        controller.invalidate_tokens();
+        assert!(matches!(
+            try_register(&mut controller, "blue"),
+            RegisterTeamResult::Err(RegistrationError::TooManyPlayers)
+        ));
+    }
+
+    #[test]
+    fn test_invalid_request_is_rejected() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        send_message(&mut client, &String::from("{\"RegisterTeam\":42}")).unwrap();
+
+        let controller = Mutex::new(Controller::new(1, 1, None));
+        let (mut stream, _) = listener.accept().unwrap();
+        assert!(handle_connection(&mut stream, &controller, &mut Services).is_err());
+        assert!(!controller.is_poisoned());
+        assert!(controller.lock().unwrap().teams.is_empty());
+    }
+}
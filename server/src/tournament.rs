@@ -0,0 +1,712 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    io,
+    net::{Shutdown, TcpStream},
+    sync::{Condvar, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use shared::{
+    enums::Message,
+    functions::{get_message, parse_incoming, send_message},
+    referee::{Referee, TeamReport},
+    simulator::SimulationConfig,
+    spectator::{SpectatorMessage, WorldUpdate},
+    structs::Position,
+};
+
+use crate::{
+    history::{self, MatchRecord, ResultStore},
+    Request,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    Waiting, // Attend que toutes les équipes soient au complet
+    Playing,
+    Finished,
+    Disconnected, // Connexion perdue en cours de partie
+    Kicked,       // Déconnecté depuis la console d'administration
+}
+
+// Ce que la console d'administration affiche d'un joueur
+#[derive(Debug, Clone)]
+pub struct PlayerStatus {
+    pub team: String,
+    pub name: String,
+    pub position: Position,
+    pub state: ConnectionState,
+    pub challenge: Option<u64>, // Modulo du challenge en cours
+}
+
+struct Connection {
+    stream: Option<TcpStream>, // Copie du socket, pour déconnecter le joueur
+    state: ConnectionState,
+    outbox: VecDeque<Message>, // Messages à envoyer au début du prochain tour du joueur
+}
+
+struct Spectator {
+    stream: TcpStream,
+    generation: Option<u64>, // Match dont le spectateur a reçu le labyrinthe
+}
+
+// Match à plusieurs équipes dans un même labyrinthe. La partie démarre quand `expected_teams`
+// équipes ont chacune `players_per_team` joueurs, chaque connexion joue ensuite dans son thread
+pub struct Tournament {
+    config: SimulationConfig,
+    expected_teams: usize,
+    state: Mutex<State>,
+    changed: Condvar, // Un joueur a rejoint ou quitté la partie, pause, reprise ou réinitialisation
+    spectators: Mutex<Vec<Spectator>>,
+    ticks: Mutex<u64>,
+    results: Option<ResultStore>, // Historique où enregistrer les parties terminées
+}
+
+struct State {
+    referee: Referee,
+    teams: HashMap<String, usize>, // Nom d'équipe -> équipe du monde
+    connections: Vec<Connection>,  // Par joueur du monde
+    finished_players: usize,
+    paused: bool,
+    generation: u64, // Incrémenté à chaque réinitialisation : les joueurs de l'ancien match s'arrêtent
+    started: Option<Instant>,
+}
+
+impl Tournament {
+    pub fn new(config: SimulationConfig, expected_teams: usize) -> Self {
+        Self {
+            state: Mutex::new(State {
+                referee: new_referee(&config),
+                teams: HashMap::new(),
+                connections: Vec::new(),
+                finished_players: 0,
+                paused: false,
+                generation: 0,
+                started: None,
+            }),
+            config,
+            expected_teams,
+            changed: Condvar::new(),
+            spectators: Mutex::new(Vec::new()),
+            ticks: Mutex::new(0),
+            results: None,
+        }
+    }
+
+    pub fn with_results(mut self, store: ResultStore) -> Self {
+        self.results = Some(store);
+        self
+    }
+
+    fn is_full(&self, state: &State) -> bool {
+        state.referee.world.players.len() >= self.expected_teams * self.config.players_per_team
+    }
+
+    fn is_over(&self, state: &State) -> bool {
+        self.is_full(state) && state.finished_players == state.referee.world.players.len()
+    }
+
+    // Tous les joueurs attendus ont terminé leur partie
+    pub fn is_finished(&self) -> bool {
+        self.is_over(&self.state.lock().unwrap())
+    }
+
+    // Joue la partie d'un joueur inscrit, jusqu'à la sortie de son équipe ou `max_turns`
+    pub fn play(&self, stream: &mut TcpStream, team: &str, player: &str) -> io::Result<()> {
+        let Some((id, generation)) = self.join(stream, team, player) else {
+            return Ok(());
+        };
+        let result = self.play_turns(stream, id, generation);
+        self.leave(id, generation, result.is_ok());
+        result
+    }
+
+    // Ajoute le joueur au monde puis attend que toutes les équipes soient au complet.
+    // None si la partie est réinitialisée pendant l'attente
+    fn join(&self, stream: &TcpStream, team: &str, player: &str) -> Option<(usize, u64)> {
+        let mut state = self.state.lock().unwrap();
+        if self.is_full(&state) {
+            println!("Partie complète, {player} ({team}) refusé");
+            return None;
+        }
+        let team = match state.teams.get(team) {
+            Some(&id) => id,
+            None => {
+                let id = state.referee.add_team(team);
+                state.teams.insert(team.to_string(), id);
+                id
+            }
+        };
+        let id = state.referee.add_player(team, player);
+        state.connections.push(Connection {
+            stream: stream.try_clone().ok(),
+            state: ConnectionState::Waiting,
+            outbox: VecDeque::new(),
+        });
+        let generation = state.generation;
+
+        if self.is_full(&state) {
+            println!("\n === La partie démarre: {} équipes ===\n", state.teams.len());
+            state.started = Some(Instant::now());
+            self.changed.notify_all();
+        } else {
+            let missing = self.expected_teams * self.config.players_per_team - state.referee.world.players.len();
+            println!("En attente de {missing} joueur(s)");
+        }
+
+        let mut state = self
+            .changed
+            .wait_while(state, |state| !self.is_full(state) && state.generation == generation)
+            .unwrap();
+        if state.generation != generation {
+            return None;
+        }
+        state.connections[id].state = ConnectionState::Playing;
+        Some((id, generation))
+    }
+
+    // Verrouille l'état quand la partie n'est pas en pause. None si le match du joueur a été
+    // réinitialisé ou s'il a été déconnecté
+    fn running(&self, id: usize, generation: u64) -> Option<MutexGuard<'_, State>> {
+        let state = self.state.lock().unwrap();
+        let active = |state: &State| {
+            state.generation == generation && state.connections[id].state == ConnectionState::Playing
+        };
+        let state = self
+            .changed
+            .wait_while(state, |state| state.paused && active(state))
+            .unwrap();
+        active(&state).then_some(state)
+    }
+
+    fn play_turns(&self, stream: &mut TcpStream, id: usize, generation: u64) -> io::Result<()> {
+        let (team, name) = {
+            let state = self.state.lock().unwrap();
+            let player = &state.referee.world.players[id];
+            (player.team, player.name.clone())
+        };
+
+        // Un message qui n'est pas une action ne consomme pas le tour : le joueur reçoit à
+        // nouveau sa vue radar, sans nouvel indice
+        let mut turn = 0;
+        let mut new_turn = true;
+        while turn < self.config.max_turns {
+            // Un coéquipier a trouvé la sortie : la partie de l'équipe est terminée
+            let (messages, radar) = {
+                let Some(mut state) = self.running(id, generation) else {
+                    break;
+                };
+                if state.referee.team_finished(team) {
+                    break;
+                }
+                // Les indices d'abord : un challenge forcé au premier tour arrive après le secret
+                let mut messages = if new_turn { state.referee.scheduled_hints(id, turn) } else { Vec::new() };
+                messages.extend(state.connections[id].outbox.drain(..));
+                (messages, state.referee.radar_view(id))
+            };
+            for message in &messages {
+                send(stream, message)?;
+            }
+            if let Some(radar) = radar {
+                send(stream, &Message::RadarView(radar))?;
+            }
+
+            let action = match parse_incoming(&get_message(stream)?)? {
+                Some(Message::Action(action)) => action,
+                Some(message) => {
+                    println!("Message inattendu de {name} ignoré (tour {turn} non compté): {message:?}");
+                    new_turn = false;
+                    continue;
+                }
+                None => {
+                    new_turn = false;
+                    continue;
+                }
+            };
+            let replies = match self.running(id, generation) {
+                Some(mut state) => state.referee.apply(id, action),
+                None => break,
+            };
+            for reply in &replies {
+                send(stream, reply)?;
+            }
+            turn += 1;
+            new_turn = true;
+        }
+
+        Ok(())
+    }
+
+    fn leave(&self, id: usize, generation: u64, completed: bool) {
+        let mut state = self.state.lock().unwrap();
+        if state.generation != generation {
+            return;
+        }
+
+        let connection = &mut state.connections[id];
+        connection.stream = None;
+        if connection.state == ConnectionState::Playing {
+            connection.state = if completed { ConnectionState::Finished } else { ConnectionState::Disconnected };
+        }
+        state.finished_players += 1;
+
+        if self.is_over(&state) {
+            print_results(&ranking(&state.referee.reports));
+            self.save_results(&state);
+        }
+        self.changed.notify_all();
+    }
+
+    fn save_results(&self, state: &State) {
+        let Some(store) = &self.results else {
+            return;
+        };
+        let record = MatchRecord {
+            finished_at: history::now(),
+            duration_ms: state.started.map_or(0, |started| started.elapsed().as_millis() as u64),
+            config: self.config.clone(),
+            teams: ranking(&state.referee.reports),
+            players: state.referee.player_reports.clone(),
+        };
+        match store.save(&record) {
+            Ok(path) => println!("Résultats enregistrés dans {}", path.display()),
+            Err(e) => println!("Impossible d'enregistrer les résultats: {e}"),
+        }
+    }
+
+    // Attend la fin de la partie de tous les joueurs, retourne les résultats par équipe
+    pub fn wait_results(&self) -> Vec<TeamReport> {
+        let state = self.state.lock().unwrap();
+        let state = self.changed.wait_while(state, |state| !self.is_over(state)).unwrap();
+        ranking(&state.referee.reports)
+    }
+
+    fn find(state: &State, team: &str, player: &str) -> Option<usize> {
+        let team = *state.teams.get(team)?;
+        let world = &state.referee.world;
+        world.team_players(team).find(|&id| world.players[id].name == player)
+    }
+
+    pub fn players(&self) -> Vec<PlayerStatus> {
+        let state = self.state.lock().unwrap();
+        let world = &state.referee.world;
+        world
+            .players
+            .iter()
+            .zip(&state.connections)
+            .map(|(player, connection)| PlayerStatus {
+                team: world.teams[player.team].clone(),
+                name: player.name.clone(),
+                position: player.position,
+                state: connection.state,
+                challenge: player.challenge,
+            })
+            .collect()
+    }
+
+    // Ferme la connexion d'un joueur, sa partie s'arrête. false si le joueur est inconnu
+    pub fn kick(&self, team: &str, player: &str) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let Some(id) = Self::find(&state, team, player) else {
+            return false;
+        };
+
+        let connection = &mut state.connections[id];
+        connection.state = ConnectionState::Kicked;
+        if let Some(stream) = connection.stream.take() {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+        self.changed.notify_all();
+        true
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.state.lock().unwrap().paused = paused;
+        self.changed.notify_all();
+    }
+
+    // Lance un challenge pour un joueur, envoyé au début de son prochain tour. Retourne son modulo
+    pub fn force_challenge(&self, team: &str, player: &str) -> Option<u64> {
+        let mut state = self.state.lock().unwrap();
+        let id = Self::find(&state, team, player)?;
+
+        let challenge = state.referee.start_challenge(id);
+        state.connections[id].outbox.push_back(challenge);
+        state.referee.world.players[id].challenge
+    }
+
+    pub fn render(&self) -> String {
+        self.state.lock().unwrap().referee.world.render()
+    }
+
+    // Nouveau match : les joueurs connectés sont déconnectés, les équipes doivent se réinscrire
+    pub fn reset(&self) {
+        let mut state = self.state.lock().unwrap();
+        for connection in &mut state.connections {
+            if let Some(stream) = connection.stream.take() {
+                let _ = stream.shutdown(Shutdown::Both);
+            }
+        }
+
+        state.referee = new_referee(&self.config);
+        state.teams.clear();
+        state.connections.clear();
+        state.finished_players = 0;
+        state.paused = false;
+        state.generation += 1;
+        state.started = None;
+        self.changed.notify_all();
+    }
+
+    // Le spectateur recevra le labyrinthe au prochain tick
+    pub fn add_spectator(&self, stream: TcpStream) {
+        // Un spectateur qui ne lit plus ne doit pas bloquer les autres
+        let _ = stream.set_write_timeout(Some(Duration::from_secs(1)));
+        self.spectators.lock().unwrap().push(Spectator { stream, generation: None });
+    }
+
+    // Envoie l'état du monde et les évènements depuis le tick précédent à chaque spectateur,
+    // précédés du labyrinthe pour les nouveaux spectateurs et après une réinitialisation.
+    // Les spectateurs déconnectés sont oubliés
+    pub fn broadcast(&self) {
+        let mut ticks = self.ticks.lock().unwrap();
+        *ticks += 1;
+        let (generation, maze, update) = {
+            let mut state = self.state.lock().unwrap();
+            let events = state.referee.take_events();
+            let update = WorldUpdate::new(*ticks, &state.referee.world, events);
+            (state.generation, state.referee.world.maze.clone(), update)
+        };
+
+        let (Ok(maze), Ok(update)) = (
+            Request::to_serde_string(SpectatorMessage::Maze(maze)),
+            Request::to_serde_string(SpectatorMessage::Tick(update)),
+        ) else {
+            return;
+        };
+        self.spectators.lock().unwrap().retain_mut(|spectator| {
+            if spectator.generation != Some(generation) {
+                if send_message(&mut spectator.stream, &maze).is_err() {
+                    return false;
+                }
+                spectator.generation = Some(generation);
+            }
+            send_message(&mut spectator.stream, &update).is_ok()
+        });
+    }
+}
+
+// Arbitre qui garde les évènements de la partie pour les spectateurs
+fn new_referee(config: &SimulationConfig) -> Referee {
+    let mut referee = Referee::new(config);
+    referee.record_events();
+    referee
+}
+
+fn send(stream: &mut TcpStream, message: &Message) -> io::Result<()> {
+    send_message(stream, &Request::to_serde_string(message)?)
+}
+
+// Classement : les équipes sorties d'abord (en moins de déplacements), puis les autres
+pub fn ranking(reports: &[TeamReport]) -> Vec<TeamReport> {
+    let mut ranking = reports.to_vec();
+    ranking.sort_by_key(|report| {
+        (
+            report.moves_to_exit.is_none(),
+            report.moves_to_exit,
+            report.wall_collisions + report.opponent_collisions,
+        )
+    });
+    ranking
+}
+
+fn print_results(ranking: &[TeamReport]) {
+    println!("\n === Résultats ===");
+    for (rank, report) in ranking.iter().enumerate() {
+        let exit = match report.moves_to_exit {
+            Some(moves) => format!("sortie en {moves} déplacements"),
+            None => format!("pas de sortie ({} déplacements)", report.moves),
+        };
+        println!(
+            "{}. {}: {exit}, murs={} adversaires={} challenges={} échecs={} monstres={}",
+            rank + 1,
+            report.name,
+            report.wall_collisions,
+            report.opponent_collisions,
+            report.challenges,
+            report.challenge_failures,
+            report.monster_catches
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env, fs,
+        net::TcpListener,
+        process,
+        sync::{Arc, Mutex},
+        thread::{self, JoinHandle},
+        time::{Duration, Instant},
+    };
+
+    use shared::{
+        enums::{Action, RegisterTeamResult},
+        functions::{connect, play, register_player, register_team},
+        game_engine::{Direction, GameState, GlobalMap, Player},
+        referee::GameEvent,
+        structs::RegisterTeam,
+        world::MonsterBehavior,
+    };
+
+    use super::*;
+    use crate::{serve, serve_spectators, Controller};
+
+    // Serveur sur un port libre, retourne le match et l'adresse du serveur
+    fn start_server(tournament: Tournament) -> (Arc<Tournament>, String) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let controller = Arc::new(Mutex::new(Controller::new(
+            tournament.expected_teams,
+            tournament.config.players_per_team as u64,
+            Some(tournament.config.seed),
+        )));
+        let tournament = Arc::new(tournament);
+
+        let server = Arc::clone(&tournament);
+        thread::spawn(move || serve(listener, controller, server));
+        (tournament, address)
+    }
+
+    // Inscrit une équipe et lance ses joueurs. Chacun joue jusqu'à ce que le serveur ferme sa
+    // connexion et retourne le nombre de tours joués
+    fn spawn_team(name: &str, address: &str) -> Vec<JoinHandle<u64>> {
+        let RegisterTeamResult::Ok { registration_token, expected_players } = register_team(name, address).unwrap() else {
+            panic!("{name}");
+        };
+
+        let game_state = Arc::new(Mutex::new(GameState::new()));
+        let map = Arc::new(Mutex::new(GlobalMap::new()));
+        (0..expected_players)
+            .map(|i| {
+                let (address, token) = (address.to_string(), registration_token.clone());
+                let (mut game_state, mut map) = (Arc::clone(&game_state), Arc::clone(&map));
+
+                thread::spawn(move || {
+                    let mut stream = connect(&address);
+                    assert!(register_player(&format!("Player-{i}"), &token, &mut stream).unwrap());
+
+                    let mut player = Player {
+                        name: format!("Player-{i}"),
+                        position: (0, 0),
+                        secret: None,
+                        direction: Direction::Front,
+                    };
+                    let mut turns = 0;
+                    while play(&mut player, &mut stream, &mut game_state, &mut map).is_ok() {
+                        turns += 1;
+                    }
+                    turns
+                })
+            })
+            .collect()
+    }
+
+    fn wait_until(condition: impl Fn() -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !condition() {
+            assert!(Instant::now() < deadline, "timeout");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_ranking() {
+        let report = |name: &str, moves_to_exit: Option<u64>, wall_collisions: u64| TeamReport {
+            name: name.to_string(),
+            moves_to_exit,
+            wall_collisions,
+            ..TeamReport::default()
+        };
+        let reports = [report("lost", None, 0), report("slow", Some(30), 0), report("fast", Some(12), 4)];
+
+        let names: Vec<String> = ranking(&reports).into_iter().map(|report| report.name).collect();
+        assert_eq!(names, ["fast", "slow", "lost"]);
+    }
+
+    #[test]
+    fn test_match_with_two_teams() {
+        let config = SimulationConfig {
+            width: 6,
+            height: 6,
+            players_per_team: 2,
+            max_turns: 40,
+            seed: 5,
+            ..SimulationConfig::default()
+        };
+        let history = env::temp_dir().join(format!("tournament-results-{}", process::id()));
+        let _ = fs::remove_dir_all(&history);
+        let (tournament, address) = start_server(Tournament::new(config, 2).with_results(ResultStore::new(&history)));
+
+        let mut players = spawn_team("blue-team", &address);
+        players.extend(spawn_team("red-team", &address));
+        for player in players {
+            assert!(player.join().unwrap() > 0);
+        }
+
+        let results = tournament.wait_results();
+        let mut names: Vec<&str> = results.iter().map(|report| report.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, ["blue-team", "red-team"]);
+        assert!(results.iter().all(|report| report.moves > 0 || report.wall_collisions > 0));
+
+        // La partie est enregistrée dans l'historique, avec les statistiques de chaque joueur
+        let records = ResultStore::new(&history).load().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].config.seed, 5);
+        assert_eq!(records[0].players.len(), 4);
+        assert!(records[0].players.iter().all(|player| player.actions > 0));
+        fs::remove_dir_all(&history).unwrap();
+    }
+
+    #[test]
+    fn test_unexpected_message_does_not_use_a_turn() {
+        let config = SimulationConfig { players_per_team: 1, max_turns: 3, seed: 2, ..SimulationConfig::default() };
+        let (tournament, address) = start_server(Tournament::new(config, 1));
+        let RegisterTeamResult::Ok { registration_token, .. } = register_team("lonely", &address).unwrap() else {
+            panic!("lonely");
+        };
+        let mut stream = connect(&address);
+        assert!(register_player("Player-0", &registration_token, &mut stream).unwrap());
+
+        // Une inscription au milieu de la partie n'est pas une action : la vue radar est renvoyée
+        let action = |stream: &mut TcpStream| {
+            send(stream, &Message::Action(Action::MoveTo(Direction::Front))).unwrap();
+        };
+        let mut radars = 0;
+        while let Ok(text) = get_message(&mut stream) {
+            if !matches!(parse_incoming(&text).unwrap(), Some(Message::RadarView(_))) {
+                continue;
+            }
+            radars += 1;
+            if radars == 1 {
+                send(&mut stream, &Message::RegisterTeam(RegisterTeam { name: String::from("late") })).unwrap();
+            } else {
+                action(&mut stream);
+            }
+        }
+
+        assert_eq!(radars, 4);
+        tournament.wait_results();
+        assert_eq!(tournament.state.lock().unwrap().referee.player_reports[0].actions, 3);
+    }
+
+    #[test]
+    fn test_admin_challenge_and_kick() {
+        let config = SimulationConfig {
+            players_per_team: 1,
+            max_turns: u64::MAX,
+            ..SimulationConfig::default()
+        };
+        let (tournament, address) = start_server(Tournament::new(config, 1));
+        let players = spawn_team("team", &address);
+        wait_until(|| tournament.players().iter().any(|player| player.state == ConnectionState::Playing));
+
+        // Le challenge part au prochain tour, le client le résout
+        assert!(tournament.force_challenge("team", "Player-0").is_some());
+        assert!(tournament.force_challenge("team", "Player-9").is_none());
+        wait_until(|| tournament.players()[0].challenge.is_none());
+
+        assert!(tournament.kick("team", "Player-0"));
+        for player in players {
+            assert!(player.join().unwrap() > 0);
+        }
+
+        let results = tournament.wait_results();
+        assert_eq!(results[0].challenges, 1);
+        assert_eq!(results[0].challenge_failures, 0);
+        assert_eq!(tournament.players()[0].state, ConnectionState::Kicked);
+    }
+
+    #[test]
+    fn test_spectators_follow_the_match() {
+        let config = SimulationConfig {
+            players_per_team: 1,
+            max_turns: u64::MAX,
+            ..SimulationConfig::default()
+        };
+        let (tournament, address) = start_server(Tournament::new(config, 1));
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let spectator_address = listener.local_addr().unwrap();
+        let server = Arc::clone(&tournament);
+        thread::spawn(move || serve_spectators(listener, server, Duration::from_millis(5)));
+
+        let mut spectator = TcpStream::connect(spectator_address).unwrap();
+        let mut receive = || -> SpectatorMessage {
+            serde_json::from_str(&get_message(&mut spectator).unwrap()).unwrap()
+        };
+        let SpectatorMessage::Maze(maze) = receive() else {
+            panic!("le labyrinthe d'abord");
+        };
+        assert_eq!(maze, tournament.state.lock().unwrap().referee.world.maze);
+
+        // Les évènements de la partie arrivent avec les mises à jour
+        let players = spawn_team("team", &address);
+        wait_until(|| tournament.players().iter().any(|player| player.state == ConnectionState::Playing));
+        let modulo = tournament.force_challenge("team", "Player-0").unwrap();
+        let mut events = Vec::new();
+        let mut teams = Vec::new();
+        while !events.iter().any(|event| matches!(event, GameEvent::ChallengeSolved { .. })) {
+            let SpectatorMessage::Tick(update) = receive() else {
+                panic!("un seul labyrinthe par match");
+            };
+            events.extend(update.events);
+            teams = update.teams;
+        }
+        assert_eq!(teams, ["team"]);
+        assert_eq!(events[0], GameEvent::ChallengeStarted { player: 0, modulo });
+
+        tournament.kick("team", "Player-0");
+        for player in players {
+            player.join().unwrap();
+        }
+
+        // Un nouveau match, un nouveau labyrinthe
+        tournament.reset();
+        while !matches!(receive(), SpectatorMessage::Maze(_)) {}
+    }
+
+    #[test]
+    fn test_same_seed_same_match() {
+        let config = SimulationConfig {
+            width: 6,
+            height: 5,
+            players_per_team: 1,
+            max_turns: 20,
+            challenge_every: Some(2),
+            seed: 17,
+            monsters: vec![MonsterBehavior::Chase, MonsterBehavior::RandomWalk],
+            hints: 3,
+            secret_every: Some(5),
+            ..SimulationConfig::default()
+        };
+
+        // Un joueur seul joue ses tours dans l'ordre : la partie ne dépend que de la graine
+        let run = || {
+            let (tournament, address) = start_server(Tournament::new(config.clone(), 1));
+            for player in spawn_team("team", &address) {
+                player.join().unwrap();
+            }
+            let results = tournament.wait_results();
+            let state = tournament.state.lock().unwrap();
+            (results, state.referee.player_reports.clone(), state.referee.world.render())
+        };
+        assert_eq!(run(), run());
+
+        let token = |seed: Option<u64>| Controller::new(1, 1, seed).tokens.generate();
+        assert_eq!(token(Some(17)), token(Some(17)));
+        assert_ne!(token(Some(17)), token(Some(18)));
+        assert_ne!(token(None), token(None));
+    }
+}
@@ -0,0 +1,127 @@
+use std::{
+    future::Future,
+    io,
+    sync::{Arc, Mutex},
+};
+
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::TcpStream,
+    task::futures::TaskLocalFuture,
+};
+
+use crate::{
+    enums::{Incoming, Message, RegisterTeamResult, SubscribePlayerResult},
+    functions::{handle_message, parse_incoming, Turn},
+    game_engine::{GameState, GlobalMap, Player},
+    recorder::{self, record_as, MessageDirection},
+    structs::{RegisterTeam, SubscribePlayer},
+};
+
+tokio::task_local! {
+    // Joueur de la tâche courante : plusieurs joueurs partagent un même thread
+    static PLAYER: String;
+}
+
+// Associe les messages de `future` au joueur `name` dans l'enregistrement (`recorder::set_player` par tâche)
+pub fn with_player<F: Future>(name: impl Into<String>, future: F) -> TaskLocalFuture<String, F> {
+    PLAYER.scope(name.into(), future)
+}
+
+fn record(direction: MessageDirection, message: &str) {
+    if PLAYER.try_with(|player| record_as(player, direction, message)).is_err() {
+        recorder::record(direction, message);
+    }
+}
+
+pub async fn send_message<W: AsyncWrite + Unpin>(stream: &mut W, message: &str) -> io::Result<()> {
+    // Envois d'abord la taille du message, ensuite le message
+    stream.write_all(&(message.len() as u32).to_le_bytes()).await?;
+    stream.write_all(message.as_bytes()).await?;
+    stream.flush().await?;
+
+    // Seul un message réellement envoyé est enregistré
+    record(MessageDirection::Sent, message);
+    Ok(())
+}
+
+pub async fn send<W: AsyncWrite + Unpin>(stream: &mut W, message: &Message) -> io::Result<()> {
+    send_message(stream, &serde_json::to_string(message)?).await
+}
+
+pub async fn get_message<R: AsyncRead + Unpin>(stream: &mut R) -> io::Result<String> {
+    // Lis la taille du message, puis le message
+    let mut size = [0_u8; 4];
+    stream.read_exact(&mut size).await?;
+
+    let mut buffer = vec![0; u32::from_le_bytes(size) as usize];
+    stream.read_exact(&mut buffer).await?;
+
+    let message = String::from_utf8(buffer).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    record(MessageDirection::Received, &message);
+    Ok(message)
+}
+
+pub async fn connect(address: &str) -> io::Result<TcpStream> {
+    TcpStream::connect(address).await
+}
+
+fn unexpected(incoming: Incoming) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("Unexpected message: {incoming:?}"))
+}
+
+pub async fn register_team(name: &str, server_address: &str) -> io::Result<RegisterTeamResult> {
+    let mut stream = connect(server_address).await?;
+    send(&mut stream, &Message::RegisterTeam(RegisterTeam { name: name.to_string() })).await?;
+
+    match Incoming::parse(&get_message(&mut stream).await?)? {
+        Incoming::Known(Message::RegisterTeamResult(result)) => Ok(result),
+        other => Err(unexpected(other)),
+    }
+}
+
+pub async fn register_player<S: AsyncRead + AsyncWrite + Unpin>(
+    name: &str,
+    token: &str,
+    stream: &mut S,
+) -> io::Result<SubscribePlayerResult> {
+    let player = Message::SubscribePlayer(SubscribePlayer {
+        name: name.to_string(),
+        registration_token: token.to_string(),
+    });
+    send(stream, &player).await?;
+
+    match Incoming::parse(&get_message(stream).await?)? {
+        Incoming::Known(Message::SubscribePlayerResult(result)) => Ok(result),
+        other => Err(unexpected(other)),
+    }
+}
+
+// Un tour de jeu, comme `functions::play`. Les verrous ne sont jamais gardés pendant une attente réseau
+pub async fn play<S: AsyncRead + AsyncWrite + Unpin>(
+    player: &mut Player,
+    stream: &mut S,
+    game_state: &Arc<Mutex<GameState>>,
+    map: &Arc<Mutex<GlobalMap>>,
+) -> io::Result<Turn> {
+    let response = loop {
+        if let Some(response) = parse_incoming(&get_message(stream).await?)? {
+            break response;
+        }
+    };
+
+    let actions = {
+        let mut game_state = game_state.lock().unwrap();
+        let mut map = map.lock().unwrap();
+        handle_message(player, &response, &mut game_state, &mut map)
+    };
+
+    for action in &actions {
+        send(stream, action).await?;
+    }
+
+    Ok(Turn {
+        received: response,
+        sent: actions,
+    })
+}
@@ -0,0 +1,212 @@
+use std::{
+    io,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::{
+    async_functions::{get_message, send, with_player},
+    enums::{Message, RegisterTeamResult, RegistrationError, SubscribePlayerResult},
+    functions::parse_incoming,
+    referee::Referee,
+    simulator::SimulationConfig,
+    structs::SubscribePlayer,
+    tokens::TokenRegistry,
+    world::World,
+};
+
+// État partagé par toutes les connexions
+struct Lobby {
+    config: SimulationConfig,
+    referee: Referee,
+    expected_teams: usize,
+    tokens: TokenRegistry<usize>, // Jeton d'inscription -> équipe
+    finished_players: usize,
+}
+
+impl Lobby {
+    fn register_team(&mut self, name: &str) -> RegisterTeamResult {
+        if name.len() < 3 {
+            return RegisterTeamResult::Err(RegistrationError::InvalidName);
+        }
+        if self.referee.world.teams.iter().any(|team| team == name) {
+            return RegisterTeamResult::Err(RegistrationError::AlreadyRegistered);
+        }
+
+        // Nouveau jeton, sauf si le match est terminé ou complet => TooManyPlayers
+        let token = match self.tokens.register(self.referee.world.teams.len()) {
+            Ok(token) => token,
+            Err(error) => return RegisterTeamResult::Err(error),
+        };
+        self.referee.add_team(name);
+
+        RegisterTeamResult::Ok {
+            expected_players: self.config.players_per_team as u8,
+            registration_token: token,
+        }
+    }
+
+    fn subscribe(&mut self, player: &SubscribePlayer) -> Result<usize, RegistrationError> {
+        let team = *self
+            .tokens
+            .get(&player.registration_token)
+            .ok_or(RegistrationError::InvalidRegistrationToken)?;
+
+        let world = &self.referee.world;
+        let members: Vec<usize> = world.team_players(team).collect();
+        if members.len() >= self.config.players_per_team {
+            return Err(RegistrationError::TooManyPlayers);
+        }
+        if members.iter().any(|&id| world.players[id].name == player.name) {
+            return Err(RegistrationError::AlreadyRegistered);
+        }
+
+        Ok(self.referee.add_player(team, &player.name))
+    }
+
+    // Fin de la partie d'un joueur. Quand tous les joueurs attendus ont fini, le match est
+    // terminé : les jetons sont invalidés et plus aucune équipe ne peut s'inscrire
+    fn finish_player(&mut self) {
+        self.finished_players += 1;
+        let players = self.referee.world.players.len();
+        if players >= self.expected_teams * self.config.players_per_team
+            && self.finished_players == players
+            && self.tokens.close()
+        {
+            println!("Jetons d'inscription invalidés");
+        }
+    }
+}
+
+// Serveur de test asynchrone : inscription comme le serveur de référence, puis partie dans un
+// labyrinthe généré (`SimulationConfig`) pour au plus `expected_teams` équipes. Une tâche par
+// connexion, sans thread par joueur
+pub struct TestServer {
+    listener: TcpListener,
+    lobby: Arc<Mutex<Lobby>>,
+}
+
+impl TestServer {
+    pub async fn bind(address: &str, config: SimulationConfig, expected_teams: usize) -> io::Result<Self> {
+        Ok(Self {
+            listener: TcpListener::bind(address).await?,
+            lobby: Arc::new(Mutex::new(Lobby {
+                referee: Referee::new(&config),
+                config,
+                expected_teams,
+                tokens: TokenRegistry::new(None, expected_teams),
+                finished_players: 0,
+            })),
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    // Copie de l'état de la partie (équipes, joueurs, positions)
+    pub fn world(&self) -> World {
+        self.lobby.lock().unwrap().referee.world.clone()
+    }
+
+    // Accepte les connexions jusqu'à une erreur du socket d'écoute
+    pub async fn run(&self) -> io::Result<()> {
+        loop {
+            let (stream, peer) = self.listener.accept().await?;
+            let lobby = Arc::clone(&self.lobby);
+
+            // En attendant l'inscription, la connexion est identifiée par son adresse
+            tokio::spawn(with_player(peer.to_string(), async move {
+                if let Err(err) = handle_connection(stream, lobby).await {
+                    println!("Connexion {peer} terminée: {err}");
+                }
+            }));
+        }
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, lobby: Arc<Mutex<Lobby>>) -> io::Result<()> {
+    let Some(request) = parse_incoming(&get_message(&mut stream).await?)? else {
+        return Ok(());
+    };
+
+    match request {
+        Message::RegisterTeam(team) => {
+            let result = lobby.lock().unwrap().register_team(&team.name);
+            send(&mut stream, &Message::RegisterTeamResult(result)).await
+        }
+        Message::SubscribePlayer(player) => {
+            let result = lobby.lock().unwrap().subscribe(&player);
+            match result {
+                Ok(id) => {
+                    send(&mut stream, &Message::SubscribePlayerResult(SubscribePlayerResult::Ok)).await?;
+                    let result = with_player(player.name, play_player(&mut stream, &lobby, id)).await;
+                    lobby.lock().unwrap().finish_player();
+                    result
+                }
+                Err(error) => send(&mut stream, &Message::SubscribePlayerResult(SubscribePlayerResult::Err(error))).await,
+            }
+        }
+        other => {
+            println!("Message inattendu avant l'inscription: {other:?}");
+            Ok(())
+        }
+    }
+}
+
+// Partie d'un joueur : les indices prévus, une vue radar (sauf pendant un challenge) puis une
+// action, jusqu'à la sortie ou `max_turns`. La connexion est fermée ensuite. Comme sur le
+// serveur de test, un message qui n'est pas une action ne consomme pas le tour : la vue radar
+// est renvoyée, sans nouvel indice
+async fn play_player(stream: &mut TcpStream, lobby: &Mutex<Lobby>, id: usize) -> io::Result<()> {
+    let (max_turns, name) = {
+        let lobby = lobby.lock().unwrap();
+        (lobby.config.max_turns, lobby.referee.world.players[id].name.clone())
+    };
+
+    let mut turn = 0;
+    let mut new_turn = true;
+    while turn < max_turns {
+        let (hints, radar) = {
+            let mut lobby = lobby.lock().unwrap();
+            let hints = if new_turn { lobby.referee.scheduled_hints(id, turn) } else { Vec::new() };
+            (hints, lobby.referee.radar_view(id))
+        };
+        for hint in &hints {
+            send(stream, hint).await?;
+        }
+        if let Some(radar) = radar {
+            send(stream, &Message::RadarView(radar)).await?;
+        }
+
+        let action = match parse_incoming(&get_message(stream).await?)? {
+            Some(Message::Action(action)) => action,
+            Some(message) => {
+                println!("Message inattendu de {name} ignoré (tour {turn} non compté): {message:?}");
+                new_turn = false;
+                continue;
+            }
+            None => {
+                new_turn = false;
+                continue;
+            }
+        };
+        let (replies, exited) = {
+            let mut lobby = lobby.lock().unwrap();
+            let replies = lobby.referee.apply(id, action);
+            (replies, lobby.referee.world.players[id].exited)
+        };
+        for reply in &replies {
+            send(stream, reply).await?;
+        }
+        if exited {
+            break;
+        }
+        turn += 1;
+        new_turn = true;
+    }
+
+    Ok(())
+}
@@ -1,84 +1,442 @@
-const BASE64_ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789+/";
- 
+use std::{
+    error::Error,
+    fmt,
+    io::{self, Read, Write},
+};
+
+// Alphabet du jeu : minuscules d'abord, contrairement à la RFC 4648
+const GAME_ALPHABET: &[u8; 64] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789+/";
+const STANDARD_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URL_SAFE_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+const INVALID: u8 = 0xFF;
+const PADDING: u8 = b'=';
+
+// Table inverse d'un alphabet, indexée par octet : INVALID si l'octet n'en fait pas partie
+const fn decode_table(alphabet: &[u8; 64]) -> [u8; 256] {
+    let mut table = [INVALID; 256];
+    let mut i = 0;
+    while i < alphabet.len() {
+        table[alphabet[i] as usize] = i as u8;
+        i += 1;
+    }
+    table
+}
+
+const GAME_TABLE: [u8; 256] = decode_table(GAME_ALPHABET);
+const STANDARD_TABLE: [u8; 256] = decode_table(STANDARD_ALPHABET);
+const URL_SAFE_TABLE: [u8; 256] = decode_table(URL_SAFE_ALPHABET);
+
+// Octets traités par passe dans les flux (multiple de 3 : pas de bits de fin au milieu du flux)
+const STREAM_CHUNK: usize = 768;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alphabet {
+    Game,     // Celui des vues radar
+    Standard, // RFC 4648 section 4
+    UrlSafe,  // RFC 4648 section 5 ('-' et '_' au lieu de '+' et '/')
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Padding {
+    None,     // Jamais de '=' : refusé au décodage
+    Required, // '=' ajouté à l'encodage, obligatoire au décodage
+    Optional, // Pas de '=' à l'encodage, accepté au décodage s'il est correct
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    pub alphabet: Alphabet,
+    pub padding: Padding,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferTooSmall {
+    pub needed: usize,
+    pub available: usize,
+}
+
+impl fmt::Display for BufferTooSmall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "output buffer too small: need {} bytes, got {}", self.needed, self.available)
+    }
+}
+
+impl Error for BufferTooSmall {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    // Longueur 4n+1 (sans les '=') : aucun encodage ne la produit
+    InvalidLength { length: usize },
+    InvalidCharacter { index: usize, character: char },
+    // '=' absent, en trop ou interdit par la configuration
+    InvalidPadding { index: usize },
+    // Bits inutilisés du dernier caractère non nuls : un autre encodage des mêmes octets
+    NonCanonicalTrailingBits { index: usize, bits: u8 },
+    BufferTooSmall(BufferTooSmall),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::InvalidLength { length } => write!(f, "invalid base64 length: {length}"),
+            DecodeError::InvalidCharacter { index, character } => {
+                write!(f, "invalid base64 character {character:?} at index {index}")
+            }
+            DecodeError::InvalidPadding { index } => write!(f, "invalid base64 padding at index {index}"),
+            DecodeError::NonCanonicalTrailingBits { index, bits } => {
+                write!(f, "non-canonical base64 trailing bits {bits:#b} at index {index}")
+            }
+            DecodeError::BufferTooSmall(error) => error.fmt(f),
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+impl From<BufferTooSmall> for DecodeError {
+    fn from(error: BufferTooSmall) -> Self {
+        DecodeError::BufferTooSmall(error)
+    }
+}
+
+// Codec base64 pour une configuration donnée
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Engine {
+    config: Config,
+    symbols: &'static [u8; 64],
+    table: &'static [u8; 256],
+}
+
+// Vues radar et messages du jeu
+pub const GAME: Engine = Engine::new(Config { alphabet: Alphabet::Game, padding: Padding::None });
+pub const STANDARD: Engine = Engine::new(Config { alphabet: Alphabet::Standard, padding: Padding::Required });
+pub const URL_SAFE: Engine = Engine::new(Config { alphabet: Alphabet::UrlSafe, padding: Padding::None });
+
+impl Engine {
+    pub const fn new(config: Config) -> Self {
+        let (symbols, table) = match config.alphabet {
+            Alphabet::Game => (GAME_ALPHABET, &GAME_TABLE),
+            Alphabet::Standard => (STANDARD_ALPHABET, &STANDARD_TABLE),
+            Alphabet::UrlSafe => (URL_SAFE_ALPHABET, &URL_SAFE_TABLE),
+        };
+        Self { config, symbols, table }
+    }
+
+    pub fn config(&self) -> Config {
+        self.config
+    }
+
+    // Nombre de caractères produits pour `len` octets, '=' compris
+    pub fn encoded_len(&self, len: usize) -> usize {
+        match self.config.padding {
+            Padding::Required => len.div_ceil(3) * 4,
+            Padding::None | Padding::Optional => (len * 8).div_ceil(6),
+        }
+    }
+
+    // Valeur (0 à 63) d'un caractère de l'alphabet
+    pub fn value_of(&self, c: char) -> Option<u8> {
+        let value = self.table[u8::try_from(c).ok()? as usize];
+        (value != INVALID).then_some(value)
+    }
+
+    // Encode dans un tampon fourni par l'appelant, retourne le nombre de caractères écrits
+    pub fn encode_into(&self, input: &[u8], output: &mut [u8]) -> Result<usize, BufferTooSmall> {
+        let len = self.encoded_len(input.len());
+        let available = output.len();
+        let output = output.get_mut(..len).ok_or(BufferTooSmall { needed: len, available })?;
+
+        // 3 octets donnent 4 caractères, un bloc incomplet est complété par des bits nuls
+        let symbols = (input.len() * 8).div_ceil(6);
+        for (chunk, out) in input.chunks(3).zip(output[..symbols].chunks_mut(4)) {
+            let block = chunk
+                .iter()
+                .enumerate()
+                .fold(0u32, |block, (i, &byte)| block | (byte as u32) << (16 - 8 * i));
+            for (i, c) in out.iter_mut().enumerate() {
+                *c = self.symbols[(block >> (18 - 6 * i)) as usize & 0b111111];
+            }
+        }
+        output[symbols..].fill(PADDING);
+
+        Ok(len)
+    }
+
+    pub fn encode(&self, input: &[u8]) -> String {
+        let mut output = vec![0u8; self.encoded_len(input.len())];
+        // Le tampon a exactement la bonne taille
+        let _ = self.encode_into(input, &mut output);
+
+        // Les alphabets sont en ASCII
+        output.into_iter().map(char::from).collect()
+    }
+
+    // Décode dans un tampon fourni par l'appelant, retourne le nombre d'octets écrits
+    pub fn decode_into(&self, input: &str, output: &mut [u8]) -> Result<usize, DecodeError> {
+        let bytes = input.as_bytes();
+        let data_len = bytes.len() - bytes.iter().rev().take(2).take_while(|&&c| c == PADDING).count();
+
+        // Si la longueur de l'entrée est 4n+1, elle est immédiatement rejetée comme invalide
+        if data_len % 4 == 1 {
+            return Err(DecodeError::InvalidLength { length: bytes.len() });
+        }
+
+        let padded = data_len < bytes.len();
+        let padding_ok = match self.config.padding {
+            Padding::None => !padded,
+            Padding::Required => bytes.len().is_multiple_of(4),
+            Padding::Optional => !padded || bytes.len().is_multiple_of(4),
+        };
+        if !padding_ok {
+            return Err(DecodeError::InvalidPadding { index: data_len });
+        }
+
+        let len = data_len * 6 / 8;
+        let available = output.len();
+        let output = output.get_mut(..len).ok_or(BufferTooSmall { needed: len, available })?;
+
+        // 4 caractères donnent 3 octets
+        for (block_index, (chunk, out)) in bytes[..data_len].chunks(4).zip(output.chunks_mut(3)).enumerate() {
+            let mut block = 0u32;
+            for (i, &c) in chunk.iter().enumerate() {
+                let value = self.table[c as usize];
+                if value == INVALID {
+                    let index = block_index * 4 + i;
+                    // Les caractères précédents sont en ASCII : `index` est une frontière de caractère
+                    let character = input[index..].chars().next().unwrap_or_default();
+                    return Err(DecodeError::InvalidCharacter { index, character });
+                }
+                block |= (value as u32) << (18 - 6 * i);
+            }
+            for (i, byte) in out.iter_mut().enumerate() {
+                *byte = (block >> (16 - 8 * i)) as u8;
+            }
+        }
+
+        // Un bloc incomplet laisse 4 (2 caractères) ou 2 (3 caractères) bits inutilisés, qui doivent être nuls
+        let unused = match data_len % 4 {
+            2 => 0b1111,
+            3 => 0b11,
+            _ => 0,
+        };
+        if unused != 0 {
+            let bits = self.table[bytes[data_len - 1] as usize] & unused;
+            if bits != 0 {
+                return Err(DecodeError::NonCanonicalTrailingBits { index: data_len - 1, bits });
+            }
+        }
+
+        Ok(len)
+    }
+
+    pub fn decode(&self, input: &str) -> Result<Vec<u8>, DecodeError> {
+        // Majorant : les '=' éventuels ne produisent aucun octet
+        let mut output = vec![0u8; input.len() * 3 / 4];
+        let len = self.decode_into(input, &mut output)?;
+        output.truncate(len);
+        Ok(output)
+    }
+}
+
+// Nombre de caractères produits pour `len` octets avec l'alphabet du jeu (pas de padding)
+pub fn encoded_len(len: usize) -> usize {
+    GAME.encoded_len(len)
+}
+
+// Nombre d'octets obtenus pour `len` caractères sans padding, None si la longueur est 4n+1
+pub fn decoded_len(len: usize) -> Option<usize> {
+    (len % 4 != 1).then_some(len * 6 / 8)
+}
+
+pub fn encode_into(input: &[u8], output: &mut [u8]) -> Result<usize, BufferTooSmall> {
+    GAME.encode_into(input, output)
+}
+
 pub fn encode(input: &[u8]) -> String {
-    let mut output = String::new();
-    let mut buffer = 0u32;
-    let mut bits = 0;
- 
-    for &byte in input {
-        buffer = (buffer << 8) | (byte as u32);
-        bits += 8;
-        while bits >= 6 {
-            bits -= 6;
-            let index = (buffer >> bits) & 0b111111;
-            output.push(BASE64_ALPHABET[index as usize] as char);
-        }
-    }
- 
-    if bits > 0 {
-        buffer <<= 6 - bits;
-        let index = buffer & 0b111111;
-        output.push(BASE64_ALPHABET[index as usize] as char);
-    }
- 
-    // while output.len() % 4 != 0 {
-    //     output.push('=');
-    // }
- 
-    output
-}
- 
-pub fn decode(input: &str) -> Result<Vec<u8>, &'static str> {
-    // Si la longueur de l'entrée est 4n+1, elle est immédiatement rejetée comme invalide
-    if input.len() % 4 == 1 {
-        return Err("Invalid Base64 length");
-    }
- 
-    let mut output = Vec::new();
-    let mut buffer = 0u32;
-    let mut bits = 0;
- 
-    for c in input.chars() {
-        // if c == '=' {
-        //     break;
-        // }
- 
-        let value = match BASE64_ALPHABET.iter().position(|&x| x as char == c) {
-            Some(v) => v as u32,
-            None => return Err("Invalid character in input"),
+    GAME.encode(input)
+}
+
+pub fn value_of(c: char) -> Option<u8> {
+    GAME.value_of(c)
+}
+
+pub fn decode_into(input: &str, output: &mut [u8]) -> Result<usize, DecodeError> {
+    GAME.decode_into(input, output)
+}
+
+pub fn decode(input: &str) -> Result<Vec<u8>, DecodeError> {
+    GAME.decode(input)
+}
+
+fn invalid_data<E: Error + Send + Sync + 'static>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+// Encodeur en flux : les octets écrits sont encodés vers `inner` par blocs de 3.
+// `finish` doit être appelé pour écrire le dernier bloc incomplet (et ses '=')
+pub struct EncoderWriter<W: Write> {
+    inner: W,
+    engine: Engine,
+    pending: [u8; 3],
+    pending_len: usize,
+}
+
+impl<W: Write> EncoderWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self::with_engine(inner, GAME)
+    }
+
+    pub fn with_engine(inner: W, engine: Engine) -> Self {
+        Self {
+            inner,
+            engine,
+            pending: [0; 3],
+            pending_len: 0,
+        }
+    }
+
+    // Écrit les derniers octets en attente et rend le flux sous-jacent
+    pub fn finish(mut self) -> io::Result<W> {
+        let mut out = [0u8; 4];
+        let len = self.engine.encode_into(&self.pending[..self.pending_len], &mut out).map_err(invalid_data)?;
+        self.inner.write_all(&out[..len])?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for EncoderWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut input = buf;
+        let mut out = [0u8; STREAM_CHUNK / 3 * 4];
+
+        // Compléter le bloc commencé lors de l'écriture précédente
+        if self.pending_len > 0 {
+            let take = (3 - self.pending_len).min(input.len());
+            self.pending[self.pending_len..self.pending_len + take].copy_from_slice(&input[..take]);
+            self.pending_len += take;
+            input = &input[take..];
+
+            if self.pending_len < 3 {
+                return Ok(buf.len());
+            }
+            let len = self.engine.encode_into(&self.pending, &mut out).map_err(invalid_data)?;
+            self.inner.write_all(&out[..len])?;
+            self.pending_len = 0;
+        }
+
+        let whole = input.len() - input.len() % 3;
+        for chunk in input[..whole].chunks(STREAM_CHUNK) {
+            let len = self.engine.encode_into(chunk, &mut out).map_err(invalid_data)?;
+            self.inner.write_all(&out[..len])?;
+        }
+
+        let rest = &input[whole..];
+        self.pending[..rest.len()].copy_from_slice(rest);
+        self.pending_len = rest.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+// Décodeur en flux : lit des caractères base64 depuis `inner` et rend les octets décodés.
+// Une erreur `InvalidData` (source `DecodeError`) est retournée sur une entrée invalide
+pub struct DecoderReader<R: Read> {
+    inner: R,
+    engine: Engine,
+    input: Vec<u8>,   // Caractères lus mais pas encore décodés
+    decoded: Vec<u8>, // Octets décodés pas encore rendus
+    position: usize,
+    finished: bool,
+}
+
+impl<R: Read> DecoderReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self::with_engine(inner, GAME)
+    }
+
+    pub fn with_engine(inner: R, engine: Engine) -> Self {
+        Self {
+            inner,
+            engine,
+            input: Vec::new(),
+            decoded: Vec::new(),
+            position: 0,
+            finished: false,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    // Décode les blocs complets de `input`, ou tout ce qui reste à la fin du flux.
+    // Le dernier bloc est gardé jusqu'à la fin : lui seul peut porter des '=' ou des bits de fin
+    fn decode_pending(&mut self, end_of_stream: bool) -> io::Result<()> {
+        let whole = if end_of_stream {
+            self.input.len()
+        } else {
+            self.input.len().saturating_sub(1) / 4 * 4
         };
- 
-        buffer = (buffer << 6) | value;
-        bits += 6;
- 
-        if bits >= 8 {
-            bits -= 8;
-            output.push(((buffer >> bits) & 0xFF) as u8);
+        if !end_of_stream {
+            if let Some(index) = self.input[..whole].iter().position(|&c| c == PADDING) {
+                return Err(invalid_data(DecodeError::InvalidPadding { index }));
+            }
         }
+        let text = std::str::from_utf8(&self.input[..whole]).map_err(invalid_data)?;
+
+        self.decoded.resize(whole / 4 * 3 + 3, 0);
+        let len = self.engine.decode_into(text, &mut self.decoded).map_err(invalid_data)?;
+        self.decoded.truncate(len);
+        self.position = 0;
+        self.input.drain(..whole);
+        Ok(())
     }
- 
-    Ok(output)
 }
- 
+
+impl<R: Read> Read for DecoderReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.decoded.len() && !self.finished {
+            let mut chunk = [0u8; STREAM_CHUNK / 3 * 4];
+            let read = self.inner.read(&mut chunk)?;
+            self.input.extend_from_slice(&chunk[..read]);
+            self.finished = read == 0;
+            self.decode_pending(self.finished)?;
+        }
+
+        let available = &self.decoded[self.position..];
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.position += len;
+        Ok(len)
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use proptest::{collection::vec, prelude::*};
+
     use super::*;
- 
+
     #[test]
     fn test_encode() {
         assert_eq!(encode(b"Hello"), "sgvSBg8");
     }
- 
+
     #[test]
     fn test_decode() {
         assert_eq!(decode("sgvSBg8").unwrap(), b"Hello");
     }
- 
+
     #[test]
     fn test_decode_invalid() {
         assert!(decode("SGVsbG@").is_err());
     }
- 
+
     #[test]
     fn test_all_case() {
         assert_eq!(encode(&[0]), "aa");
@@ -92,4 +450,150 @@ mod tests {
         assert_eq!(encode(b"Hello, World!"), "sgvSBg8SifDVCMXKiq");
         assert_eq!(encode(&(0..=255).collect::<Vec<u8>>()), "aaecaWqfbGCicqOlda0odXareHmufryxgbKAgXWDhH8GisiJjcuMjYGPkISSls4VmdeYmZq1nJC4otO7pd0+p0bbqKneruzhseLks0XntK9quvjtvfvwv1HzwLTCxv5FygfIy2rLzMDOAwPRBg1UB3bXCNn0Dxz3EhL6E3X9FN+aGykdHiwgH4IjIOUmJy6pKjgsK5svLPEyMzQBNj2EN6cHOQoKPAANQkMQQ6YTRQ+WSBkZTlw2T7I5URU8VB6/WmhcW8tfXSFiYCRlZm3oZ9dr0Tpu1DBx2nNA29ZD3T/G4ElJ5oxM5+JP6UVS7E7V8phY8/t19VF4+FR7/p3+/W");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_into_buffers() {
+        let mut encoded = [0u8; 16];
+        assert_eq!(encode_into(b"Hello", &mut encoded), Ok(7));
+        assert_eq!(&encoded[..7], b"sgvSBg8");
+        assert!(encode_into(b"Hello", &mut [0u8; 6]).is_err());
+
+        let mut decoded = [0u8; 16];
+        assert_eq!(decode_into("sgvSBg8", &mut decoded), Ok(5));
+        assert_eq!(&decoded[..5], b"Hello");
+        assert!(decode_into("sgvSBg8", &mut [0u8; 4]).is_err());
+        assert!(decode_into("sgvSB", &mut decoded).is_err());
+
+        assert_eq!(value_of('a'), Some(0));
+        assert_eq!(value_of('/'), Some(63));
+        assert_eq!(value_of('='), None);
+        assert_eq!(value_of('é'), None);
+    }
+
+    #[test]
+    fn test_streams() {
+        let input: Vec<u8> = (0..=255).cycle().take(2000).collect();
+        let expected = encode(&input);
+
+        // Écritures de tailles variées, à cheval sur les blocs de 3 octets
+        let mut writer = EncoderWriter::new(Vec::new());
+        for chunk in input.chunks(7).chain(std::iter::once(&[][..])) {
+            writer.write_all(chunk).unwrap();
+        }
+        let written = writer.finish().unwrap();
+        assert_eq!(written, expected.as_bytes());
+
+        // Lecture octet par octet depuis le flux sous-jacent
+        let mut reader = DecoderReader::new(io::BufReader::with_capacity(1, expected.as_bytes()));
+        let mut decoded = Vec::new();
+        reader.read_to_end(&mut decoded).unwrap();
+        assert_eq!(decoded, input);
+
+        let mut reader = DecoderReader::new("sgvS@g8".as_bytes());
+        let error = reader.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(DecoderReader::new("sgvSB".as_bytes()).read_to_end(&mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_engines() {
+        // Vecteurs de la RFC 4648
+        let vectors = [("", ""), ("f", "Zg=="), ("fo", "Zm8="), ("foo", "Zm9v"), ("foob", "Zm9vYg=="), ("fooba", "Zm9vYmE="), ("foobar", "Zm9vYmFy")];
+        for (plain, encoded) in vectors {
+            assert_eq!(STANDARD.encode(plain.as_bytes()), encoded);
+            assert_eq!(STANDARD.decode(encoded).unwrap(), plain.as_bytes());
+        }
+
+        assert_eq!(URL_SAFE.encode(&[0xfb, 0xff]), "-_8");
+        assert_eq!(STANDARD.encode(&[0xfb, 0xff]), "+/8=");
+        assert_eq!(URL_SAFE.decode("-_8").unwrap(), [0xfb, 0xff]);
+        assert_eq!(GAME.encode(b"Hello"), encode(b"Hello"));
+
+        let optional = Engine::new(Config { alphabet: Alphabet::Standard, padding: Padding::Optional });
+        assert_eq!(optional.encode(b"f"), "Zg");
+        assert_eq!(optional.decode("Zg").unwrap(), b"f");
+        assert_eq!(optional.decode("Zg==").unwrap(), b"f");
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(STANDARD.decode("Zg"), Err(DecodeError::InvalidPadding { index: 2 }));
+        assert_eq!(STANDARD.decode("Zg="), Err(DecodeError::InvalidPadding { index: 2 }));
+        assert_eq!(URL_SAFE.decode("Zg=="), Err(DecodeError::InvalidPadding { index: 2 }));
+        assert_eq!(STANDARD.decode("Zm9v="), Err(DecodeError::InvalidPadding { index: 4 }));
+        assert_eq!(STANDARD.decode("Z==="), Err(DecodeError::InvalidCharacter { index: 1, character: '=' }));
+        assert_eq!(STANDARD.decode("Zm9vY"), Err(DecodeError::InvalidLength { length: 5 }));
+        assert_eq!(URL_SAFE.decode("+/8"), Err(DecodeError::InvalidCharacter { index: 0, character: '+' }));
+        assert_eq!(decode("sgé"), Err(DecodeError::InvalidCharacter { index: 2, character: 'é' }));
+
+        // "Zh" et "Zg" donnent le même octet, seul "Zg" est canonique
+        assert_eq!(STANDARD.decode("Zh=="), Err(DecodeError::NonCanonicalTrailingBits { index: 1, bits: 1 }));
+        assert_eq!(decode("sgvSBg9"), Err(DecodeError::NonCanonicalTrailingBits { index: 6, bits: 1 }));
+        assert_eq!(
+            decode_into("sgvSBg8", &mut [0u8; 4]),
+            Err(DecodeError::BufferTooSmall(BufferTooSmall { needed: 5, available: 4 }))
+        );
+        assert_eq!(
+            DecodeError::NonCanonicalTrailingBits { index: 6, bits: 1 }.to_string(),
+            "non-canonical base64 trailing bits 0b1 at index 6"
+        );
+    }
+
+    #[test]
+    fn test_streams_with_padding() {
+        let input = b"streams with padding";
+        let mut writer = EncoderWriter::with_engine(Vec::new(), STANDARD);
+        for byte in input {
+            writer.write_all(&[*byte]).unwrap();
+        }
+        let written = writer.finish().unwrap();
+        assert_eq!(written, STANDARD.encode(input).as_bytes());
+
+        let mut reader = DecoderReader::with_engine(io::BufReader::with_capacity(1, written.as_slice()), STANDARD);
+        let mut decoded = Vec::new();
+        reader.read_to_end(&mut decoded).unwrap();
+        assert_eq!(decoded, input);
+
+        // '=' au milieu du flux
+        let mut reader = DecoderReader::with_engine("Zg==Zg==".as_bytes(), STANDARD);
+        assert!(reader.read_to_end(&mut Vec::new()).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_round_trip(bytes in vec(any::<u8>(), 0..256)) {
+            for engine in [GAME, STANDARD, URL_SAFE] {
+                let encoded = engine.encode(&bytes);
+                prop_assert_eq!(encoded.len(), engine.encoded_len(bytes.len()));
+                prop_assert_eq!(engine.decode(&encoded).unwrap(), bytes.clone());
+            }
+        }
+
+        // Tout texte accepté est l'unique encodage de ses octets
+        #[test]
+        fn prop_decode_is_canonical(text in "[a-zA-Z0-9+/=é]{0,40}") {
+            if let Ok(bytes) = decode(&text) {
+                prop_assert_eq!(encode(&bytes), text.clone());
+            }
+            if let Ok(bytes) = STANDARD.decode(&text) {
+                prop_assert_eq!(STANDARD.encode(&bytes), text);
+            }
+        }
+
+        #[test]
+        fn prop_streams_match_slices(bytes in vec(any::<u8>(), 0..2000), split in 1usize..50) {
+            let mut writer = EncoderWriter::new(Vec::new());
+            for chunk in bytes.chunks(split) {
+                writer.write_all(chunk).unwrap();
+            }
+            let written = writer.finish().unwrap();
+            prop_assert_eq!(written.clone(), encode(&bytes).into_bytes());
+
+            let mut decoded = Vec::new();
+            DecoderReader::new(io::BufReader::with_capacity(split, written.as_slice()))
+                .read_to_end(&mut decoded)
+                .unwrap();
+            prop_assert_eq!(decoded, bytes);
+        }
+    }
+}
@@ -1,7 +1,8 @@
 
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-use crate::{game_engine::Direction, structs::{RegisterTeam, SubscribePlayer}};
+use crate::{game_engine::Direction, radar_view::{RadarView, RawRadarView}, structs::{RegisterTeam, SubscribePlayer}};
 
 #[derive(Deserialize, Serialize, Debug)]
 pub enum RegisterTeamResult {
@@ -30,20 +31,93 @@ pub enum Message {
     RegisterTeamResult(RegisterTeamResult),
     SubscribePlayer(SubscribePlayer),
     SubscribePlayerResult(SubscribePlayerResult),
-    RadarView(String),
+    RadarView(RadarView),
     Action(Action),
     Challenge(Challenge),
     ActionError(ActionError),
     Hint(Hint),
 }
 
+// Seule la vue radar est lue, sans la décoder
+#[derive(Deserialize)]
+enum RawRadarViewMessage {
+    RadarView(RawRadarView),
+}
+
+impl Message {
+    // Chaîne brute d'un message RadarView, même si la vue est invalide. Pour déboguer
+    // un message que `serde_json::from_str::<Message>` refuse
+    pub fn raw_radar_view(text: &str) -> Option<RawRadarView> {
+        match serde_json::from_str(text) {
+            Ok(RawRadarViewMessage::RadarView(raw)) => Some(raw),
+            Err(_) => None,
+        }
+    }
+}
+
+// Message reçu du serveur. Un message ou une variante que cette version ne connaît pas
+// (nouvel indice, nouveau challenge...) est gardé en JSON brut au lieu d'être une erreur
+#[derive(Debug)]
+pub enum Incoming {
+    Known(Message),
+    Unknown(Value),
+}
+
+// Variantes connues, dans l'ordre des enums ci-dessous. Serde ne permet pas de les lister : à
+// mettre à jour avec les enums
+const MESSAGE_TAGS: [&str; 9] = [
+    "RegisterTeam",
+    "RegisterTeamResult",
+    "SubscribePlayer",
+    "SubscribePlayerResult",
+    "RadarView",
+    "Action",
+    "Challenge",
+    "ActionError",
+    "Hint",
+];
+const CHALLENGE_TAGS: [&str; 1] = ["SecretSumModulo"];
+const HINT_TAGS: [&str; 3] = ["RelativeCompass", "GridSize", "Secret"];
+
+// Variante d'une valeur sérialisée par serde (`"Variante"` ou `{"Variante": contenu}`)
+fn variant(value: &Value) -> Option<(&str, Option<&Value>)> {
+    match value {
+        Value::String(tag) => Some((tag, None)),
+        Value::Object(map) if map.len() == 1 => map.iter().next().map(|(tag, inner)| (tag.as_str(), Some(inner))),
+        _ => None,
+    }
+}
+
+impl Incoming {
+    // Un texte qui n'est pas du JSON, ou une variante connue mal formée, est une erreur
+    pub fn parse(text: &str) -> serde_json::Result<Incoming> {
+        Incoming::from_value(serde_json::from_str(text)?)
+    }
+
+    pub fn from_value(value: Value) -> serde_json::Result<Incoming> {
+        let unknown_in = |inner: Option<&Value>, tags: &[&str]| {
+            inner.and_then(variant).is_some_and(|(tag, _)| !tags.contains(&tag))
+        };
+        let unknown = match variant(&value) {
+            Some((tag, _)) if !MESSAGE_TAGS.contains(&tag) => true,
+            Some(("Challenge", inner)) => unknown_in(inner, &CHALLENGE_TAGS),
+            Some(("Hint", inner)) => unknown_in(inner, &HINT_TAGS),
+            _ => false,
+        };
+        if unknown {
+            return Ok(Incoming::Unknown(value));
+        }
+        Message::deserialize(&value).map(Incoming::Known)
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 pub enum Action {
     MoveTo(Direction),
     SolveChallenge { answer: String },
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ActionError {
     CannotPassThroughWall,
     CannotPassThroughOpponent,
@@ -69,3 +143,39 @@ pub enum SubscribePlayerResult {
     Ok,
     Err(RegistrationError),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_incoming_keeps_unknown_messages() {
+        assert!(matches!(Incoming::parse(r#"{"Hint":{"Secret":4}}"#), Ok(Incoming::Known(Message::Hint(Hint::Secret(4))))));
+
+        // Nouvelle variante d'indice, de challenge ou de message
+        for text in [
+            r#"{"Hint":{"Treasure":{"x":1,"y":2}}}"#,
+            r#"{"Challenge":{"SecretSumProduct":7}}"#,
+            r#"{"EndOfGame":{"winner":"team"}}"#,
+            r#""EndOfRound""#,
+        ] {
+            match Incoming::parse(text) {
+                Ok(Incoming::Unknown(value)) => assert_eq!(value, serde_json::from_str::<Value>(text).unwrap()),
+                other => panic!("{text}: {other:?}"),
+            }
+        }
+
+        // Une variante connue mal formée reste une erreur
+        for text in [
+            r#"{"RadarView":"ieysGjGO8papd/b"}"#,
+            r#"{"Hint":{"Secret":"four"}}"#,
+            r#"{"Challenge":{"SecretSumModulo":-1}}"#,
+            r#"{"Hint":[1,2]}"#,
+            r#"{"Action":{"MoveTo":"Up"}}"#,
+        ] {
+            assert!(Incoming::parse(text).is_err(), "{text}");
+        }
+
+        assert!(Incoming::parse("{not json").is_err());
+    }
+}
@@ -1,118 +1,115 @@
 use std::{
-    io::{Read, Write},
-    net::TcpStream, sync::{Arc, Mutex, MutexGuard},
+    io::{self, Read, Write},
+    net::TcpStream, sync::{Arc, Mutex},
 };
 
 use crate::{
     enums::{
-        Action, ActionError, Challenge, Hint, Message, RegisterTeamResult, SubscribePlayerResult
+        Action, ActionError, Challenge, Hint, Incoming, Message, RegisterTeamResult, SubscribePlayerResult
     },
     game_engine::{Direction, GameState, GlobalMap, Player},
-    radar_view::{decode_radarview, RadarView},
+    recorder::{record, MessageDirection},
     structs::{RegisterTeam, SubscribePlayer},
 };
 
-pub fn send_message(stream: &mut TcpStream, message: &String) {
+pub fn send_message(stream: &mut TcpStream, message: &String) -> io::Result<()> {
     // Envois d'abord la taille du message, ensuite le message
     let size = message.len() as u32;
-    stream.write(&size.to_le_bytes()).unwrap();
-    stream.write(message.as_bytes()).unwrap();
+    stream.write_all(&size.to_le_bytes())?;
+    stream.write_all(message.as_bytes())?;
+
+    // Seul un message réellement envoyé est enregistré
+    record(MessageDirection::Sent, message);
+    Ok(())
 }
 
-pub fn get_message(stream: &mut TcpStream) -> String {
+pub fn get_message(stream: &mut TcpStream) -> io::Result<String> {
     //Lis la réponse du server
     let mut recieved_message_len_buffer = [0_u8; 4];
 
     println!("{:?}",stream);
 
     //Lis la taille du message envoyer par le server
-    stream.read_exact(&mut recieved_message_len_buffer).unwrap();
+    stream.read_exact(&mut recieved_message_len_buffer)?;
     // Converti la taille recue (en octet) en entier
     let n = u32::from_le_bytes(recieved_message_len_buffer);
 
     // remplis le buffer en fonction de la taille du message
     let mut message_recieved_buffer = vec![0; n as usize];
-    stream.read_exact(&mut message_recieved_buffer).unwrap();
+    stream.read_exact(&mut message_recieved_buffer)?;
 
     // Converti le message d'octet en chaine de charactère et le retourne
-    match String::from_utf8(message_recieved_buffer) {
-        Ok(message) => message,
-        Err(err) => {
-            panic!("Err: {:?}", err)
-        }
-    }
+    let message = String::from_utf8(message_recieved_buffer)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    record(MessageDirection::Received, &message);
+    Ok(message)
 }
 
-pub fn register_team(name: &str, server_adress: &str) -> RegisterTeamResult {
-    let mut stream = connect(server_adress);
+// Valeur d'une option de la ligne de commande, ex: `--record trace.jsonl`
+pub fn get_arg_value(args: &[String], flag: &str) -> Option<String> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|index| args.get(index + 1))
+        .cloned()
+}
 
-    print!(
-        "Register team stream addr: {:?}",
-        &stream.local_addr().unwrap().port()
-    );
+// Inscrit une équipe. Une erreur signifie que le serveur est injoignable ou a répondu autre
+// chose qu'un RegisterTeamResult
+pub fn register_team(name: &str, server_adress: &str) -> io::Result<RegisterTeamResult> {
+    let mut stream = TcpStream::connect(server_adress)?;
+    print!("Register team stream addr: {:?}", stream.local_addr()?.port());
 
     let register_team_message = Message::RegisterTeam(RegisterTeam {
         name: String::from(name),
     });
 
     //Transform le message en json
-    match serde_json::to_string(&register_team_message) {
-        Ok(message) => {
-            println!("{message}");
-
-            send_message(&mut stream, &message);
-            let message_text: String = get_message(&mut stream);
-
-            // Passe d'une chaine à une structure ou un enum
-            println!("{:?}", &message_text);
-            let message: Message = serde_json::from_str(&message_text).unwrap();
-
-            if let Message::RegisterTeamResult(register_team_result) = message {
-                register_team_result
-            } else {
-                panic!("Unexpected message type")
-            }
-        }
-
-        Err(error) => {
-            panic!("{error}");
-        }
+    let message = serde_json::to_string(&register_team_message)?;
+    println!("{message}");
+    send_message(&mut stream, &message)?;
+    let message_text: String = get_message(&mut stream)?;
+
+    // Passe d'une chaine à une structure ou un enum
+    println!("{:?}", &message_text);
+    match serde_json::from_str(&message_text)? {
+        Message::RegisterTeamResult(register_team_result) => Ok(register_team_result),
+        other => Err(unexpected(&other)),
     }
 }
 
-pub fn send_move(stream: &mut TcpStream, direction: &Direction) {
+fn unexpected(message: &Message) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("Unexpected message: {message:?}"))
+}
+
+pub fn send_move(stream: &mut TcpStream, direction: &Direction) -> io::Result<()> {
     let action_move_message = Message::Action(Action::MoveTo(*direction));
-    print!("Move to: {:?}\n", *direction);
-    let action_move_message_stringify = serde_json::to_string(&action_move_message).unwrap();
-    send_message(stream, &action_move_message_stringify);
+    println!("Move to: {:?}", *direction);
+    let action_move_message_stringify = serde_json::to_string(&action_move_message)?;
+    send_message(stream, &action_move_message_stringify)
 }
 
-pub fn resolve_secret_sum_challenge(stream: &mut TcpStream, game_state:&mut MutexGuard<'_, GameState>) {
+// Calcule la réponse au challenge SecretSumModulo avec les secrets connus de l'équipe
+pub fn resolve_secret_sum_challenge(player: &Player, game_state: &mut GameState) -> Message {
     let answer = game_state.calculate_secret_sum_modulo(game_state.modulo);
     let action = Message::Action(Action::SolveChallenge {
         answer: answer.to_string(),
     });
-    let action_message_string = serde_json::to_string(&action).unwrap();
-    print!("Action message string: {:?}\n", &action_message_string);
-    send_message(stream, &action_message_string);
+    println!("Action message: {:?}", &action);
+    game_state.stats_mut(&player.name).challenges_answered += 1;
+    action
 }
 
-pub fn play(player: &mut Player, mut stream: &mut TcpStream, game_state_clone:&mut  Arc<Mutex<GameState>>, map_clone: &mut Arc<Mutex<GlobalMap>>) {
-    
-    let mut game_state = game_state_clone.lock().unwrap();
-
-
-    let message = get_message(&mut stream);
-    let response: Message = serde_json::from_str(&message).unwrap();
+// Décide de la réponse du joueur à un message du serveur, sans aucun accès réseau.
+// Retourne les messages à envoyer au serveur, dans l'ordre
+pub fn handle_message(player: &mut Player, message: &Message, game_state: &mut GameState, map: &mut GlobalMap) -> Vec<Message> {
+    let mut responses = Vec::new();
 
     // Si c'est une radaview
-    if let Message::RadarView(encoded_string) = &response {
+    if let Message::RadarView(radar) = message {
         println!("\n ==== Reception d'une radaview ===\n");
-        let mut map = map_clone.lock().unwrap();
-        let radar: RadarView = decode_radarview(&encoded_string).unwrap();
 
         // Mettre à jour la carte avec les nouvelles informations
-        map.update_from_radar(&radar);
+        map.update_from_radar(radar);
 
         // Choisir un déplacement
         let direction = map.next_move(map.player_direction);
@@ -123,25 +120,29 @@ pub fn play(player: &mut Player, mut stream: &mut TcpStream, game_state_clone:&m
         map.move_player(direction);
 
 
+        player.direction = direction;
+        game_state.stats_mut(&player.name).moves += 1;
+
         // Envoyer le mouvement au serveur
-        send_move(&mut stream, &direction);
+        println!("Move to: {:?}", direction);
+        responses.push(Message::Action(Action::MoveTo(direction)));
 
     }
 
     // Si c'est un challenge
-    if let Message::Challenge(challenge) = &response {
+    if let Message::Challenge(challenge) = message {
         println!("\n === Reception d'un Challenge {:?} ===\n", challenge);
         match challenge {
             Challenge::SecretSumModulo(modulo) => {
                 // Stocker le modulo
                 game_state.modulo = *modulo;
-                resolve_secret_sum_challenge(stream, &mut game_state);
+                responses.push(resolve_secret_sum_challenge(player, game_state));
             }
         }
     }
 
     // Si c'est un indice
-    if let Message::Hint(hint) = &response {
+    if let Message::Hint(hint) = message {
         println!("\n ==== Reception d'un indice ===\n");
         match hint {
             Hint::Secret(secret) => {
@@ -156,63 +157,101 @@ pub fn play(player: &mut Player, mut stream: &mut TcpStream, game_state_clone:&m
     }
 
     // Si c'est une Action error
-    if let Message::ActionError(error) = &response {
+    if let Message::ActionError(error) = message {
         println!("\n ==== Reception d'une action error {:?} ===\n", error);
 
         match error {
-            ActionError::CannotPassThroughWall => print!("Cannot pass through wall!"),
+            ActionError::CannotPassThroughWall => {
+                println!("Cannot pass through wall!");
+                game_state.stats_mut(&player.name).wall_collisions += 1;
+            },
             ActionError::CannotPassThroughOpponent => print!("Cannot pass through opponent!"),
             ActionError::NoRunningChallenge => print!("No Running challenge!"),
             ActionError::SolveChallengeFirst => print!("Solve challenge first!"),
             ActionError::InvalidChallengeSolution => {
                 println!("Invalid challenge solution");
-                resolve_secret_sum_challenge(stream, &mut game_state);
+                game_state.stats_mut(&player.name).challenge_failures += 1;
+                responses.push(resolve_secret_sum_challenge(player, game_state));
             },
         }
     }
+
+    responses
 }
 
-pub fn register_player(name: &str, token: &String, mut stream: &mut TcpStream) -> bool {
-    print!(
-        "Register player stream addr: {:?}\n",
-        &stream.local_addr().unwrap().port()
-    );
+// Lit un message du serveur. Un message que cette version ne comprend pas est affiché et
+// donne None : le joueur l'ignore et attend le suivant. Un message connu mal formé est une erreur
+pub fn parse_incoming(text: &str) -> serde_json::Result<Option<Message>> {
+    let incoming = Incoming::parse(text).inspect_err(|err| {
+        if let Some(raw) = Message::raw_radar_view(text) {
+            println!("Vue radar invalide {:?}: {}", raw.0, err);
+        }
+    })?;
+    match incoming {
+        Incoming::Known(message) => Ok(Some(message)),
+        Incoming::Unknown(value) => {
+            println!("Message inconnu ignoré: {}", value);
+            Ok(None)
+        }
+    }
+}
+
+// Message reçu pendant un tour et réponses envoyées au serveur
+#[derive(Debug)]
+pub struct Turn {
+    pub received: Message,
+    pub sent: Vec<Message>,
+}
+
+// Joue un tour : lit un message du serveur et y répond.
+// Une erreur signifie que la connexion est fermée (fin de partie) ou inutilisable
+pub fn play(player: &mut Player, stream: &mut TcpStream, game_state_clone:&mut  Arc<Mutex<GameState>>, map_clone: &mut Arc<Mutex<GlobalMap>>) -> io::Result<Turn> {
+    // Lire avant de verrouiller l'état : les coéquipiers continuent de jouer pendant l'attente.
+    // Les messages que cette version ne comprend pas sont ignorés, le joueur attend le suivant
+    let response = loop {
+        if let Some(response) = parse_incoming(&get_message(stream)?)? {
+            break response;
+        }
+    };
+
+    let mut game_state = game_state_clone.lock().unwrap();
+    let mut map = map_clone.lock().unwrap();
+    let actions = handle_message(player, &response, &mut game_state, &mut map);
+
+    for action in &actions {
+        send_message(stream, &serde_json::to_string(action)?)?;
+    }
+
+    Ok(Turn {
+        received: response,
+        sent: actions,
+    })
+}
+
+// Inscrit un joueur sur sa connexion. Faux si le serveur refuse l'inscription, une erreur si la
+// connexion est fermée (arrêt du client) ou si le serveur répond autre chose
+pub fn register_player(name: &str, token: &String, stream: &mut TcpStream) -> io::Result<bool> {
+    println!("Register player stream addr: {:?}", stream.local_addr()?.port());
 
     let player: Message = Message::SubscribePlayer(SubscribePlayer {
         name: String::from(name),
         registration_token: String::from(token),
     });
 
-    match serde_json::to_string(&player) {
-        Ok(player_string) => {
-            send_message(&mut stream, &player_string);
-            let response: String = get_message(&mut stream);
-
-            let result: Message = serde_json::from_str(&response).unwrap();
-
-            if let Message::SubscribePlayerResult(subscribe_player_result) = result {
-                match subscribe_player_result {
-                    SubscribePlayerResult::Ok => {
-                        println!("Joueur bien enregistrée");
-                        true
-                    },
-
-                    SubscribePlayerResult::Err(err) => {
-                        print!("Error occur: {:?}", err);
-                        false
-                    }
-                }
-            } else {
-                panic!("Unexpected message type");
-            }
+    send_message(stream, &serde_json::to_string(&player)?)?;
+    match serde_json::from_str(&get_message(stream)?)? {
+        Message::SubscribePlayerResult(SubscribePlayerResult::Ok) => {
+            println!("Joueur bien enregistrée");
+            Ok(true)
         }
-        Err(error) => {
-            panic!("{error}");
+        Message::SubscribePlayerResult(SubscribePlayerResult::Err(err)) => {
+            print!("Error occur: {:?}", err);
+            Ok(false)
         }
+        other => Err(unexpected(&other)),
     }
 }
 
 pub fn connect(addr: &str) -> TcpStream {
-    let connection = TcpStream::connect(addr).unwrap();
-    connection
+    TcpStream::connect(addr).unwrap()
 }
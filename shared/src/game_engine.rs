@@ -1,7 +1,18 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use crate::radar_view::{Cell, Element, Entity, RadarView};
+use crate::radar_view::{Cell, Element, Entity, RadarView, WallState};
+
+// Mur entre deux cases voisines de la carte globale, la plus petite case en premier
+pub type Edge = ((i32, i32), (i32, i32));
+
+fn edge(a: (i32, i32), b: (i32, i32)) -> Edge {
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Player {
@@ -36,13 +47,32 @@ pub enum GlobalCell {
     Goal,
 }
 
-#[derive(Debug)]
+// Statistiques d'un joueur sur la partie
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerStats {
+    pub moves: u64,
+    pub wall_collisions: u64,
+    pub challenges_answered: u64,
+    pub challenge_failures: u64,
+}
+
+#[derive(Debug, Default)]
 pub struct GameState {
     pub team_secrets: HashMap<String, u64>, // Secrets des coéquipiers (nom -> secret)
     pub modulo: u64,
+    pub stats: HashMap<String, PlayerStats>, // Statistiques par joueur (nom -> stats)
 }
 
 impl GameState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Statistiques du joueur, créées au premier accès
+    pub fn stats_mut(&mut self, player_name: &str) -> &mut PlayerStats {
+        self.stats.entry(player_name.to_string()).or_default()
+    }
+
     // Met à jour le secret du joueur courant ou des coéquipiers
     pub fn update_secret(&mut self, player_name: &str, secret: u64) {
         self.team_secrets.insert(player_name.to_string(), secret);
@@ -52,7 +82,7 @@ impl GameState {
     pub fn calculate_secret_sum_modulo(&self, modulo: u64) -> u64 {
         let mut total: u128 = 0;
 
-        print!("\n === Modulo={} ===\n", modulo);
+        println!("\n === Modulo={} ===", modulo);
 
         // Ajoute les secrets des coéquipiers
         for &secret in self.team_secrets.values() {
@@ -65,16 +95,28 @@ impl GameState {
 
 pub struct GlobalMap {
     pub map: HashMap<(i32, i32), GlobalCell>,
+    pub walls: HashMap<Edge, bool>, // Murs vus par le radar (true = mur, false = passage)
+    pub hints: HashSet<(i32, i32)>, // Cases contenant un indice
+    pub monsters: HashSet<(i32, i32)>, // Monstres vus lors de la dernière vue radar
     pub player_pos: (i32, i32), // Position actuelle du joueur
     pub player_direction: Direction,
 }
 
+impl Default for GlobalMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl GlobalMap {
     pub fn new() -> Self {
         let mut map = HashMap::new();
         map.insert((0, 0), GlobalCell::Player); // Position initiale
         Self {
             map,
+            walls: HashMap::new(),
+            hints: HashSet::new(),
+            monsters: HashSet::new(),
             player_pos: (0, 0),
             player_direction: Direction::Front,
         }
@@ -96,6 +138,18 @@ impl GlobalMap {
                             .or_insert(GlobalCell::Unknown);
                     }
                     Cell::Valid { element, entity } => {
+                        // Les indices et monstres sont mémorisés à part pour l'affichage
+                        if element == Element::Hint {
+                            self.hints.insert((global_x, global_y));
+                        } else {
+                            self.hints.remove(&(global_x, global_y));
+                        }
+                        if entity == Entity::Monster {
+                            self.monsters.insert((global_x, global_y));
+                        } else {
+                            self.monsters.remove(&(global_x, global_y));
+                        }
+
                         if let Element::Target = element {
                             self.map.insert((global_x, global_y), GlobalCell::Goal);
                         } else if let Entity::Ally = entity {
@@ -108,6 +162,32 @@ impl GlobalMap {
                 //self.map.insert((global_x, global_y), cell);
             }
         }
+
+        // Murs horizontaux : 4 rangées de 3, au-dessus de chaque case de la vue
+        for row in 0..4 {
+            for column in 0..3 {
+                let below = (px + column - 1, py + row - 1);
+                let above = (below.0, below.1 - 1);
+                self.update_wall(above, below, radar.horizontal[(row * 3 + column) as usize]);
+            }
+        }
+
+        // Murs verticaux : 3 rangées de 4, à gauche de chaque case de la vue
+        for row in 0..3 {
+            for column in 0..4 {
+                let right = (px + column - 1, py + row - 1);
+                let left = (right.0 - 1, right.1);
+                self.update_wall(left, right, radar.vertical[(row * 4 + column) as usize]);
+            }
+        }
+    }
+
+    fn update_wall(&mut self, a: (i32, i32), b: (i32, i32), state: WallState) {
+        match state {
+            WallState::Wall => self.walls.insert(edge(a, b), true),
+            WallState::Open => self.walls.insert(edge(a, b), false),
+            WallState::Undefined => None,
+        };
     }
 
     pub fn next_move(&self, facing: Direction) -> Direction {
@@ -167,4 +247,107 @@ impl GlobalMap {
             self.player_pos = new_pos; // Met à jour la position du joueur
         }
     }
+
+    // Représentation texte de la carte explorée : chaque case est entourée de ses murs
+    // ('-' et '|'), '@' est la position du joueur, 'H' un indice et 'M' un monstre
+    pub fn render(&self) -> String {
+        let positions = self.map.keys();
+        let (mut min_x, mut max_x, mut min_y, mut max_y) = (i32::MAX, i32::MIN, i32::MAX, i32::MIN);
+        for &(x, y) in positions {
+            min_x = min_x.min(x);
+            max_x = max_x.max(x);
+            min_y = min_y.min(y);
+            max_y = max_y.max(y);
+        }
+        if min_x > max_x {
+            return String::new();
+        }
+
+        let wall = |a: (i32, i32), b: (i32, i32), symbol: char| match self.walls.get(&edge(a, b)) {
+            Some(true) => symbol,
+            _ => ' ',
+        };
+
+        let mut output = String::new();
+        for y in min_y..=max_y {
+            // Ligne des murs au-dessus de la rangée
+            for x in min_x..=max_x {
+                output.push('+');
+                output.push(wall((x, y - 1), (x, y), '-'));
+            }
+            output.push_str("+\n");
+
+            // Ligne des cases et des murs verticaux
+            for x in min_x..=max_x {
+                output.push(wall((x - 1, y), (x, y), '|'));
+                output.push(self.cell_symbol((x, y)));
+            }
+            output.push(wall((max_x, y), (max_x + 1, y), '|'));
+            output.push('\n');
+        }
+
+        for x in min_x..=max_x {
+            output.push('+');
+            output.push(wall((x, max_y), (x, max_y + 1), '-'));
+        }
+        output.push_str("+\n");
+        output
+    }
+
+    fn cell_symbol(&self, position: (i32, i32)) -> char {
+        if position == self.player_pos {
+            return '@';
+        }
+        if self.monsters.contains(&position) {
+            return 'M';
+        }
+        if self.hints.contains(&position) {
+            return 'H';
+        }
+        match self.map.get(&position) {
+            None => ' ',
+            Some(GlobalCell::Unknown) => '?',
+            Some(GlobalCell::Wall) => '#',
+            Some(GlobalCell::Open) => '.',
+            Some(GlobalCell::Player) => 'P',
+            Some(GlobalCell::Goal) => 'G',
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::radar_view::decode_radarview;
+
+    #[test]
+    fn test_update_from_radar_records_walls() {
+        let mut map = GlobalMap::new();
+        let mut radar = RadarView {
+            horizontal: vec![WallState::Undefined; 12],
+            vertical: vec![WallState::Undefined; 12],
+            cells: vec![Cell::Valid { element: Element::None, entity: Entity::None }; 9],
+        };
+        radar.horizontal[3 + 1] = WallState::Wall; // Au-dessus du joueur
+        radar.vertical[4 + 2] = WallState::Open; // À droite du joueur
+        radar.cells[0] = Cell::Valid { element: Element::Hint, entity: Entity::Monster };
+
+        map.update_from_radar(&radar);
+
+        assert_eq!(map.walls.get(&edge((0, -1), (0, 0))), Some(&true));
+        assert_eq!(map.walls.get(&edge((0, 0), (1, 0))), Some(&false));
+        assert!(map.hints.contains(&(-1, -1)));
+        assert!(map.monsters.contains(&(-1, -1)));
+        assert_eq!(map.render().lines().nth(2), Some("+ +-+ +"));
+    }
+
+    #[test]
+    fn test_render_sample_radar() {
+        let mut map = GlobalMap::new();
+        map.update_from_radar(&decode_radarview("ieysGjGO8papd/a").unwrap());
+
+        let rendered = map.render();
+        assert_eq!(rendered.lines().count(), 7);
+        assert!(rendered.contains('@'));
+    }
 }
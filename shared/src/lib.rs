@@ -4,3 +4,16 @@ pub mod functions;
 pub mod radar_view;
 pub mod base64;
 pub mod game_engine;
+pub mod shutdown;
+pub mod recorder;
+pub mod replay;
+pub mod maze;
+pub mod world;
+pub mod referee;
+pub mod simulator;
+pub mod spectator;
+pub mod tokens;
+#[cfg(feature = "async")]
+pub mod async_functions;
+#[cfg(feature = "async")]
+pub mod async_server;
@@ -0,0 +1,351 @@
+use std::collections::VecDeque;
+
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game_engine::Direction,
+    radar_view::{Cell, Element, Entity, RadarView, WallState},
+    structs::Position,
+};
+
+// Orientation absolue d'un joueur dans le labyrinthe (le radar est relatif à celle-ci)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Heading {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Heading {
+    const CLOCKWISE: [Heading; 4] = [Heading::North, Heading::East, Heading::South, Heading::West];
+
+    // Déplacement (x, y) d'une case dans cette orientation, y croît vers le sud
+    pub fn delta(self) -> (i64, i64) {
+        match self {
+            Heading::North => (0, -1),
+            Heading::East => (1, 0),
+            Heading::South => (0, 1),
+            Heading::West => (-1, 0),
+        }
+    }
+
+    // Orientation obtenue après un déplacement relatif
+    pub fn turn(self, direction: Direction) -> Heading {
+        let quarter_turns = match direction {
+            Direction::Front => 0,
+            Direction::Right => 1,
+            Direction::Back => 2,
+            Direction::Left => 3,
+        };
+        let index = Self::CLOCKWISE.iter().position(|&h| h == self).unwrap_or(0);
+        Self::CLOCKWISE[(index + quarter_turns) % 4]
+    }
+
+    // Angle en degrés, sens horaire depuis le nord
+    pub fn degrees(self) -> f32 {
+        match self {
+            Heading::North => 0.0,
+            Heading::East => 90.0,
+            Heading::South => 180.0,
+            Heading::West => 270.0,
+        }
+    }
+}
+
+// Ce que le radar doit afficher pour une case occupée
+pub trait Occupants {
+    fn entity_at(&self, x: i64, y: i64) -> Entity;
+    fn element_at(&self, x: i64, y: i64) -> Element;
+}
+
+// Labyrinthe parfait : chaque case est reliée à toutes les autres par un unique chemin
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Maze {
+    pub width: usize,
+    pub height: usize,
+    pub start: Position,
+    pub exit: Position,
+    // Mur au nord de la case (x, y), index y * width + x. La rangée `height` est le bord sud
+    horizontal_walls: Vec<bool>,
+    // Mur à l'ouest de la case (x, y), index y * (width + 1) + x. La colonne `width` est le bord est
+    vertical_walls: Vec<bool>,
+}
+
+impl Maze {
+    // Génère un labyrinthe par parcours en profondeur aléatoire (recursive backtracker)
+    pub fn generate<R: Rng + ?Sized>(width: usize, height: usize, rng: &mut R) -> Self {
+        assert!(width > 0 && height > 0, "Maze must have at least one cell");
+
+        let mut maze = Maze {
+            width,
+            height,
+            start: Position { x: 0, y: 0 },
+            exit: Position { x: 0, y: 0 },
+            horizontal_walls: vec![true; (height + 1) * width],
+            vertical_walls: vec![true; height * (width + 1)],
+        };
+
+        let start = Position {
+            x: rng.random_range(0..width),
+            y: rng.random_range(0..height),
+        };
+
+        let mut visited = vec![false; width * height];
+        let mut stack = vec![start];
+        visited[start.y * width + start.x] = true;
+
+        while let Some(&current) = stack.last() {
+            let mut headings = Heading::CLOCKWISE;
+            headings.shuffle(rng);
+
+            let next = headings.into_iter().find_map(|heading| {
+                let (x, y) = maze.neighbour(current, heading)?;
+                (!visited[y * width + x]).then_some((heading, Position { x, y }))
+            });
+
+            match next {
+                Some((heading, position)) => {
+                    maze.set_wall(current, heading, false);
+                    visited[position.y * width + position.x] = true;
+                    stack.push(position);
+                }
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+
+        // La sortie est la case la plus éloignée du départ
+        maze.start = start;
+        maze.exit = maze.farthest_from(start);
+        maze
+    }
+
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    pub fn has_wall(&self, position: Position, heading: Heading) -> bool {
+        let Position { x, y } = position;
+        match heading {
+            Heading::North => self.horizontal_walls[y * self.width + x],
+            Heading::South => self.horizontal_walls[(y + 1) * self.width + x],
+            Heading::West => self.vertical_walls[y * (self.width + 1) + x],
+            Heading::East => self.vertical_walls[y * (self.width + 1) + x + 1],
+        }
+    }
+
+    pub fn set_wall(&mut self, position: Position, heading: Heading, wall: bool) {
+        let Position { x, y } = position;
+        match heading {
+            Heading::North => self.horizontal_walls[y * self.width + x] = wall,
+            Heading::South => self.horizontal_walls[(y + 1) * self.width + x] = wall,
+            Heading::West => self.vertical_walls[y * (self.width + 1) + x] = wall,
+            Heading::East => self.vertical_walls[y * (self.width + 1) + x + 1] = wall,
+        }
+    }
+
+    // Case voisine dans le labyrinthe, sans tenir compte des murs
+    pub fn neighbour(&self, position: Position, heading: Heading) -> Option<(usize, usize)> {
+        let (dx, dy) = heading.delta();
+        let (x, y) = (position.x as i64 + dx, position.y as i64 + dy);
+        self.contains(x, y).then_some((x as usize, y as usize))
+    }
+
+    // Case atteinte en avançant, None s'il y a un mur
+    pub fn step(&self, position: Position, heading: Heading) -> Option<Position> {
+        if self.has_wall(position, heading) {
+            return None;
+        }
+        self.neighbour(position, heading).map(|(x, y)| Position { x, y })
+    }
+
+    // Distances (en nombre de pas) de chaque case à `from`, index y * width + x
+    pub fn distances_from(&self, from: Position) -> Vec<Option<usize>> {
+        let mut distances = vec![None; self.width * self.height];
+        let mut queue = VecDeque::from([from]);
+        distances[from.y * self.width + from.x] = Some(0);
+
+        while let Some(current) = queue.pop_front() {
+            let distance = distances[current.y * self.width + current.x].unwrap_or(0);
+            for heading in Heading::CLOCKWISE {
+                if let Some(next) = self.step(current, heading) {
+                    let index = next.y * self.width + next.x;
+                    if distances[index].is_none() {
+                        distances[index] = Some(distance + 1);
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+
+        distances
+    }
+
+    fn farthest_from(&self, from: Position) -> Position {
+        let distances = self.distances_from(from);
+        let index = (0..distances.len())
+            .max_by_key(|&index| distances[index])
+            .unwrap_or(0);
+        Position {
+            x: index % self.width,
+            y: index / self.width,
+        }
+    }
+
+    // État du mur entre deux cases adjacentes (coordonnées absolues, éventuellement hors du labyrinthe)
+    fn edge_between(&self, a: (i64, i64), b: (i64, i64)) -> WallState {
+        let (inside_a, inside_b) = (self.contains(a.0, a.1), self.contains(b.0, b.1));
+        if !inside_a && !inside_b {
+            return WallState::Undefined;
+        }
+        if !inside_a || !inside_b {
+            // Le bord du labyrinthe est toujours un mur
+            return WallState::Wall;
+        }
+
+        let from = Position { x: a.0 as usize, y: a.1 as usize };
+        let heading = match (b.0 - a.0, b.1 - a.1) {
+            (0, -1) => Heading::North,
+            (1, 0) => Heading::East,
+            (0, 1) => Heading::South,
+            _ => Heading::West,
+        };
+
+        if self.has_wall(from, heading) {
+            WallState::Wall
+        } else {
+            WallState::Open
+        }
+    }
+
+    // Vue radar 3x3 centrée sur `position`, tournée pour que le haut soit l'orientation du joueur
+    pub fn radar_view(&self, position: Position, heading: Heading, occupants: &impl Occupants) -> RadarView {
+        let (fx, fy) = heading.delta();
+        let (rx, ry) = (-fy, fx); // Vecteur "droite" du joueur
+        let (px, py) = (position.x as i64, position.y as i64);
+
+        // Case relative (colonne, rangée) de la vue radar vers coordonnées absolues, (1, 1) est le joueur
+        let to_absolute = |column: i64, row: i64| {
+            let (dx, dy) = (column - 1, row - 1);
+            (px + dx * rx - dy * fx, py + dx * ry - dy * fy)
+        };
+
+        // 4 rangées de 3 murs horizontaux : le mur au-dessus de la case (colonne, rangée)
+        let mut horizontal = Vec::with_capacity(12);
+        for row in 0..4 {
+            for column in 0..3 {
+                horizontal.push(self.edge_between(to_absolute(column, row - 1), to_absolute(column, row)));
+            }
+        }
+
+        // 3 rangées de 4 murs verticaux : le mur à gauche de la case (colonne, rangée)
+        let mut vertical = Vec::with_capacity(12);
+        for row in 0..3 {
+            for column in 0..4 {
+                vertical.push(self.edge_between(to_absolute(column - 1, row), to_absolute(column, row)));
+            }
+        }
+
+        let mut cells = Vec::with_capacity(9);
+        for row in 0..3 {
+            for column in 0..3 {
+                let (x, y) = to_absolute(column, row);
+                cells.push(if self.contains(x, y) {
+                    let element = if (x as usize, y as usize) == (self.exit.x, self.exit.y) {
+                        Element::Target
+                    } else {
+                        occupants.element_at(x, y)
+                    };
+                    Cell::Valid {
+                        element,
+                        entity: occupants.entity_at(x, y),
+                    }
+                } else {
+                    Cell::Invalid
+                });
+            }
+        }
+
+        RadarView {
+            horizontal,
+            vertical,
+            cells,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    struct Empty;
+
+    impl Occupants for Empty {
+        fn entity_at(&self, _x: i64, _y: i64) -> Entity {
+            Entity::None
+        }
+        fn element_at(&self, _x: i64, _y: i64) -> Element {
+            Element::None
+        }
+    }
+
+    #[test]
+    fn test_generated_maze_is_connected_and_seeded() {
+        let maze = Maze::generate(8, 6, &mut StdRng::seed_from_u64(7));
+        assert!(maze.distances_from(maze.start).iter().all(|distance| distance.is_some()));
+        assert_ne!(maze.start, maze.exit);
+
+        let same = Maze::generate(8, 6, &mut StdRng::seed_from_u64(7));
+        assert_eq!(maze, same);
+    }
+
+    #[test]
+    fn test_heading_turn() {
+        assert_eq!(Heading::North.turn(Direction::Right), Heading::East);
+        assert_eq!(Heading::West.turn(Direction::Right), Heading::North);
+        assert_eq!(Heading::East.turn(Direction::Back), Heading::West);
+        assert_eq!(Heading::South.turn(Direction::Left), Heading::East);
+    }
+
+    #[test]
+    fn test_radar_view_is_relative_to_heading() {
+        // Couloir de 3 cases ouvert d'ouest en est
+        let mut maze = Maze::generate(3, 1, &mut StdRng::seed_from_u64(0));
+        let center = Position { x: 1, y: 0 };
+        maze.set_wall(center, Heading::West, false);
+        maze.set_wall(center, Heading::East, false);
+
+        let north = maze.radar_view(center, Heading::North, &Empty);
+        // Vers le nord : rangée du haut hors du labyrinthe, murs latéraux du joueur ouverts
+        assert_eq!(north.cells[1], Cell::Invalid);
+        assert_eq!(north.vertical[4 + 1], WallState::Open);
+        assert_eq!(north.vertical[4 + 2], WallState::Open);
+        assert_eq!(north.horizontal[3 + 1], WallState::Wall);
+        assert_eq!(north.horizontal[0], WallState::Undefined);
+
+        let east = maze.radar_view(center, Heading::East, &Empty);
+        // Vers l'est : le couloir est devant et derrière le joueur
+        assert!(matches!(east.cells[1], Cell::Valid { .. }));
+        assert!(matches!(east.cells[7], Cell::Valid { .. }));
+        assert_eq!(east.cells[3], Cell::Invalid);
+        assert_eq!(east.horizontal[3 + 1], WallState::Open);
+        assert_eq!(east.vertical[4 + 1], WallState::Wall);
+    }
+
+    #[test]
+    fn test_radar_views_are_rotations_of_each_other() {
+        let maze = Maze::generate(6, 6, &mut StdRng::seed_from_u64(5));
+        let position = Position { x: 2, y: 3 };
+        let north = maze.radar_view(position, Heading::North, &Empty);
+
+        // Se tourner vers l'est fait passer le côté est de la vue en haut
+        assert_eq!(maze.radar_view(position, Heading::East, &Empty), north.rotate_counter_clockwise());
+        assert_eq!(maze.radar_view(position, Heading::South, &Empty), north.rotate_half_turn());
+        assert_eq!(maze.radar_view(position, Heading::West, &Empty), north.rotate_clockwise());
+    }
+}
@@ -1,6 +1,67 @@
-use crate::base64::{decode, encode};
+use std::{error::Error, fmt};
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::base64::{decode_into, decoded_len, encode, DecodeError};
+
+// Partie de la vue radar encodée où le décodage a échoué
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RadarSection {
+    Horizontal,
+    Vertical,
+    Cells,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RadarDecodeError {
+    // La chaîne n'est pas du base64 valide
+    Base64(DecodeError),
+    // Nombre d'octets décodés différent des 11 attendus
+    InvalidLength { expected: usize, actual: usize },
+    // Valeur 3 (0b11) pour un mur
+    InvalidWall { section: RadarSection, index: usize, bits: u8 },
+    // Élément 0b11 dans une case qui n'est pas la case invalide 0b1111
+    InvalidCell { index: usize, bits: u8 },
+    // Bits de bourrage non nuls : dixième quartet des cases ou fin du dernier caractère base64
+    NonZeroPadding { section: Option<RadarSection>, bits: u8 },
+}
+
+impl fmt::Display for RadarDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RadarDecodeError::Base64(error) => write!(f, "invalid radar view base64: {error}"),
+            RadarDecodeError::InvalidLength { expected, actual } => {
+                write!(f, "invalid radar view length: expected {expected} bytes, got {actual}")
+            }
+            RadarDecodeError::InvalidWall { section, index, bits } => {
+                write!(f, "invalid {section:?} wall #{index}: bits {bits:02b}")
+            }
+            RadarDecodeError::InvalidCell { index, bits } => {
+                write!(f, "invalid cell #{index}: bits {bits:04b}")
+            }
+            RadarDecodeError::NonZeroPadding { section: Some(section), bits } => {
+                write!(f, "non-zero padding after {section:?}: bits {bits:b}")
+            }
+            RadarDecodeError::NonZeroPadding { section: None, bits } => {
+                write!(f, "non-zero base64 padding bits: {bits:b}")
+            }
+        }
+    }
+}
+
+impl Error for RadarDecodeError {}
+
+impl From<DecodeError> for RadarDecodeError {
+    fn from(error: DecodeError) -> Self {
+        match error {
+            // Les 2 derniers bits du 15e caractère
+            DecodeError::NonCanonicalTrailingBits { bits, .. } => RadarDecodeError::NonZeroPadding { section: None, bits },
+            error => RadarDecodeError::Base64(error),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 pub enum WallState {
     Undefined,
     Open,
@@ -8,16 +69,16 @@ pub enum WallState {
 }
 
 impl WallState {
-    fn from_bits(bits: u8) -> Result<Self, &'static str> {
+    fn from_bits(bits: u8) -> Option<Self> {
         match bits {
-            0 => Ok(WallState::Undefined),
-            1 => Ok(WallState::Open),
-            2 => Ok(WallState::Wall),
-            _ => Err("Invalid wall state bits"),
+            0 => Some(WallState::Undefined),
+            1 => Some(WallState::Open),
+            2 => Some(WallState::Wall),
+            _ => None,
         }
     }
 
-    fn to_bits(&self) -> u8 {
+    fn to_bits(self) -> u8 {
         match self {
             WallState::Undefined => 0,
             WallState::Open => 1,
@@ -26,14 +87,14 @@ impl WallState {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 pub enum Element {
     None,
     Hint,
     Target,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 pub enum Entity {
     None,
     Ally,
@@ -41,16 +102,16 @@ pub enum Entity {
     Monster,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 pub enum Cell {
     Valid { element: Element, entity: Entity },
     Invalid,
 }
 
 impl Cell {
-    fn from_bits(bits: u8) -> Result<Self, &'static str> {
+    fn from_bits(bits: u8) -> Option<Self> {
         if bits == 0b1111 {
-            return Ok(Cell::Invalid);
+            return Some(Cell::Invalid);
         }
         let element_bits = (bits >> 2) & 0b11;
         let entity_bits = bits & 0b11;
@@ -59,22 +120,20 @@ impl Cell {
             0b00 => Element::None,
             0b01 => Element::Hint,
             0b10 => Element::Target,
-            _ => return Err("Invalid element bits"),
+            _ => return None,
         };
 
         let entity = match entity_bits {
             0b00 => Entity::None,
             0b01 => Entity::Ally,
             0b10 => Entity::Enemy,
-            0b11 => Entity::Monster,
-            _ => return Err("Invalid Entity"),
-
+            _ => Entity::Monster,
         };
 
-        Ok(Cell::Valid { element, entity })
+        Some(Cell::Valid { element, entity })
     }
 
-    fn to_bits(&self) -> u8 {
+    fn to_bits(self) -> u8 {
         match self {
             Cell::Invalid => 0b1111,
             Cell::Valid { element, entity } => {
@@ -95,152 +154,291 @@ impl Cell {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+// Case de la grille 7x7 : les cases de la vue sont aux positions impaires,
+// les murs entre elles et les coins aux positions paires
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GridCell {
-    Unknown,
-    Wall,
-    Player,
-    Open,
-    Goal // Target
+    Corner,
+    Wall(WallState),
+    Cell(Cell),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderStyle {
+    Ascii,
+    Unicode,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+// Sur le réseau, une vue radar est sa chaîne base64 compacte (voir les impl serde plus bas)
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RadarView {
     pub horizontal: Vec<WallState>,
     pub vertical: Vec<WallState>,
     pub cells: Vec<Cell>,
 }
 
+impl Serialize for RadarView {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let encoded = encode_radarview(self).map_err(ser::Error::custom)?;
+        serializer.serialize_str(&encoded)
+    }
+}
+
+impl<'de> Deserialize<'de> for RadarView {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        decode_radarview(&encoded).map_err(de::Error::custom)
+    }
+}
+
+// Vue radar telle qu'elle circule sur le réseau, sans décodage.
+// Permet d'afficher ou d'enregistrer une vue que le décodeur refuse
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RawRadarView(pub String);
+
+impl RawRadarView {
+    pub fn decode(&self) -> Result<RadarView, RadarDecodeError> {
+        decode_radarview(&self.0)
+    }
+}
+
+impl TryFrom<&RadarView> for RawRadarView {
+    type Error = &'static str;
+
+    fn try_from(radar: &RadarView) -> Result<Self, Self::Error> {
+        encode_radarview(radar).map(RawRadarView)
+    }
+}
+
 impl RadarView {
-    fn radar_to_grid(&self) -> [[GridCell; 3]; 3] {
-        let mut grid = [[GridCell::Unknown; 3]; 3];
-
-        for (i, cell) in self.cells.iter().enumerate() {
-            let x = i % 3;
-            let y = i / 3;
-
-            grid[y][x] = match cell {
-                Cell::Invalid => GridCell::Unknown,
-                Cell::Valid { element, entity } => {
-                    if *element == Element::Target {
-                        GridCell::Goal
-                    } else if *entity == Entity::Ally {
-                        GridCell::Player
-                    } else {
-                        GridCell::Open
-                    }
-                }
-            }
+    // Place les 3x3 cases, les 4x3 murs horizontaux et les 3x4 murs verticaux sur une grille 7x7
+    pub fn to_grid(&self) -> [[GridCell; 7]; 7] {
+        let mut grid = [[GridCell::Corner; 7]; 7];
+
+        for (i, cell) in self.cells.iter().enumerate().take(9) {
+            grid[(i / 3) * 2 + 1][(i % 3) * 2 + 1] = GridCell::Cell(*cell);
+        }
+
+        // Mur horizontal (rangée r, colonne c) : au-dessus de la case (c, r)
+        for (i, wall) in self.horizontal.iter().enumerate().take(12) {
+            grid[(i / 3) * 2][(i % 3) * 2 + 1] = GridCell::Wall(*wall);
+        }
+
+        // Mur vertical (rangée r, colonne c) : à gauche de la case (c, r)
+        for (i, wall) in self.vertical.iter().enumerate().take(12) {
+            grid[(i / 4) * 2 + 1][(i % 4) * 2] = GridCell::Wall(*wall);
         }
 
         grid
     }
 
-    fn add_horizontal_walls(&self, grid: &mut [[GridCell; 3]; 3]) {
-        for y in 0..2 {
-            for x in 0..2 {
-                let index = y * 3 + x;
-                if let WallState::Wall = self.horizontal[index] {
-                    grid[y * 2 + 1][x * 2] = GridCell::Wall
-                }
-            }
+    // Inverse de `to_grid` : les coins sont ignorés
+    pub fn from_grid(grid: &[[GridCell; 7]; 7]) -> RadarView {
+        let wall = |grid_cell: GridCell| match grid_cell {
+            GridCell::Wall(wall) => wall,
+            _ => WallState::Undefined,
+        };
+        let cell = |grid_cell: GridCell| match grid_cell {
+            GridCell::Cell(cell) => cell,
+            _ => Cell::Invalid,
+        };
+
+        RadarView {
+            horizontal: (0..12).map(|i| wall(grid[(i / 3) * 2][(i % 3) * 2 + 1])).collect(),
+            vertical: (0..12).map(|i| wall(grid[(i / 4) * 2 + 1][(i % 4) * 2])).collect(),
+            cells: (0..9).map(|i| cell(grid[(i / 3) * 2 + 1][(i % 3) * 2 + 1])).collect(),
         }
     }
 
-    fn add_vertical_walls(&self, grid: &mut [[GridCell; 3]; 3]) {
-        for y in 0..3 {
-            for x in 0..2 {
-                let index = y * 2 + x;
-                if let WallState::Wall = self.vertical[index] {
-                    grid[y * 2][x * 2 + 1] = GridCell::Wall;  // Position des murs verticaux
-                }
+    // Déplace chaque élément de la grille 7x7 : `position(x, y)` donne sa nouvelle place.
+    // Les murs horizontaux deviennent verticaux (et inversement) sur un quart de tour
+    fn transform(&self, position: impl Fn(usize, usize) -> (usize, usize)) -> RadarView {
+        let grid = self.to_grid();
+        let mut transformed = [[GridCell::Corner; 7]; 7];
+
+        for (y, row) in grid.iter().enumerate() {
+            for (x, grid_cell) in row.iter().enumerate() {
+                let (new_x, new_y) = position(x, y);
+                transformed[new_y][new_x] = *grid_cell;
             }
         }
+
+        RadarView::from_grid(&transformed)
+    }
+
+    // Quart de tour dans le sens horaire : la colonne de gauche passe en haut
+    pub fn rotate_clockwise(&self) -> RadarView {
+        self.transform(|x, y| (6 - y, x))
+    }
+
+    // Quart de tour dans le sens anti-horaire : la colonne de droite passe en haut
+    pub fn rotate_counter_clockwise(&self) -> RadarView {
+        self.transform(|x, y| (y, 6 - x))
+    }
+
+    pub fn rotate_half_turn(&self) -> RadarView {
+        self.transform(|x, y| (6 - x, 6 - y))
+    }
+
+    // Rotation horaire d'un angle multiple de 90 degrés (négatif pour le sens anti-horaire)
+    // Tout autre angle n'a pas de sens sur la grille : None
+    pub fn rotate(&self, degrees: i32) -> Option<RadarView> {
+        match degrees.rem_euclid(360) {
+            0 => Some(self.clone()),
+            90 => Some(self.rotate_clockwise()),
+            180 => Some(self.rotate_half_turn()),
+            270 => Some(self.rotate_counter_clockwise()),
+            _ => None,
+        }
+    }
+
+    // Miroir gauche/droite
+    pub fn reflect_horizontal(&self) -> RadarView {
+        self.transform(|x, y| (6 - x, y))
     }
 
-    fn print_grid(grid: &[[GridCell; 3]; 3]) {
-        for row in grid.iter() {
-            for cell in row.iter() {
-                match cell {
-                    GridCell::Unknown => print!("# "),  // Case inexplorée
-                    GridCell::Wall => print!("█ "),    // Mur
-                    GridCell::Open => print!(". "),    // Passage
-                    GridCell::Player => print!("P "),  // Joueur
-                    GridCell::Goal => print!("G "),    // Objectif
-                }
+    // Miroir haut/bas
+    pub fn reflect_vertical(&self) -> RadarView {
+        self.transform(|x, y| (x, 6 - y))
+    }
+
+    // Rendu texte de la vue, 7 lignes de 7 caractères
+    pub fn render(&self, style: RenderStyle) -> String {
+        let grid = self.to_grid();
+        let mut output = String::new();
+
+        for (y, row) in grid.iter().enumerate() {
+            for (x, grid_cell) in row.iter().enumerate() {
+                output.push(match grid_cell {
+                    GridCell::Corner => corner_symbol(&grid, x, y, style),
+                    GridCell::Wall(wall) => wall_symbol(*wall, y % 2 == 0, style),
+                    GridCell::Cell(cell) => cell_symbol(*cell, style),
+                });
             }
-            println!();
+            output.push('\n');
         }
+
+        output
     }
-    
-    
 }
 
-// DÉCODEUR
-fn decode_walls(bytes: &[u8], expected_count: usize) -> Result<Vec<WallState>, &'static str> {
-    if bytes.len() != 3 {
-        return Err("Invalid wall bytes length");
+fn wall_symbol(wall: WallState, horizontal: bool, style: RenderStyle) -> char {
+    match (wall, style) {
+        (WallState::Open, _) => ' ',
+        (WallState::Undefined, RenderStyle::Ascii) => '#',
+        (WallState::Undefined, RenderStyle::Unicode) => '░',
+        (WallState::Wall, RenderStyle::Ascii) => if horizontal { '-' } else { '|' },
+        (WallState::Wall, RenderStyle::Unicode) => if horizontal { '─' } else { '│' },
     }
+}
 
+fn cell_symbol(cell: Cell, style: RenderStyle) -> char {
+    match cell {
+        Cell::Invalid => match style {
+            RenderStyle::Ascii => '#',
+            RenderStyle::Unicode => '░',
+        },
+        Cell::Valid { entity: Entity::Ally, .. } => 'A',
+        Cell::Valid { entity: Entity::Enemy, .. } => 'E',
+        Cell::Valid { entity: Entity::Monster, .. } => 'M',
+        Cell::Valid { element: Element::Hint, .. } => 'H',
+        Cell::Valid { element: Element::Target, .. } => 'G',
+        Cell::Valid { .. } => ' ',
+    }
+}
+
+// Un coin relie les murs qui l'entourent, il est inconnu si aucun mur autour n'est connu
+fn corner_symbol(grid: &[[GridCell; 7]; 7], x: usize, y: usize, style: RenderStyle) -> char {
+    let neighbour = |dx: isize, dy: isize| {
+        let (nx, ny) = (x as isize + dx, y as isize + dy);
+        if !(0..7).contains(&nx) || !(0..7).contains(&ny) {
+            return WallState::Undefined;
+        }
+        match grid[ny as usize][nx as usize] {
+            GridCell::Wall(wall) => wall,
+            _ => WallState::Undefined,
+        }
+    };
+    let sides = [neighbour(0, -1), neighbour(1, 0), neighbour(0, 1), neighbour(-1, 0)];
+
+    if sides.iter().all(|&wall| wall == WallState::Undefined) {
+        return wall_symbol(WallState::Undefined, true, style);
+    }
+    if style == RenderStyle::Ascii {
+        return '+';
+    }
+
+    // Index sur 4 bits : haut, droite, bas, gauche
+    let index = sides
+        .iter()
+        .fold(0, |bits, &wall| (bits << 1) | (wall == WallState::Wall) as usize);
+    [
+        '·', '╴', '╷', '┐', '╶', '─', '┌', '┬', '╵', '┘', '│', '┤', '└', '┴', '├', '┼',
+    ][index]
+}
+
+// DÉCODEUR
+// 12 murs de 2 bits sur 3 octets, lus comme un entier little-endian : pas de bourrage
+fn decode_walls(bytes: &[u8; 3], section: RadarSection) -> Result<Vec<WallState>, RadarDecodeError> {
     // Le ">> 8" décale des bits vers la droite
     // Le Décalage à droite (>> n) : Ajoute n zéros à gauche et supprime n bits à droite.
     // Le Décalage à gauche (<< n) : Ajoute n zéros à droite et supprime n bits à gauche.
-    // En gros le décalage permet de supprimer le padding à la fin
     let combined = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0]);
 
-    //print!("{:08b} \n", &combined);
+    let mut walls = Vec::with_capacity(12);
+    for index in 0..12 {
+        let shift = 22 - index * 2;
+        let bits = ((combined >> shift) & 0b11) as u8;
 
-    let mut walls = Vec::with_capacity(expected_count);
-    for i in 0..expected_count {
-        let shift = 22 - i * 2;
-        let two_bits = ((combined >> shift) & 0b11) as u8;
-
-        //print!("{:02b} \n", &two_bits);
-
-        let wall = WallState::from_bits(two_bits)?;
+        let wall = WallState::from_bits(bits)
+            .ok_or(RadarDecodeError::InvalidWall { section, index, bits })?;
         walls.push(wall);
     }
 
     Ok(walls)
 }
 
-fn decode_cells(bytes: &[u8]) -> Result<Vec<Cell>, &'static str> {
-    if bytes.len() != 5 {
-        return Err("Invalid cell bytes length");
-    }
-
+// 9 cases de 4 bits sur 5 octets, le dixième quartet est du bourrage et doit être nul
+fn decode_cells(bytes: &[u8; 5]) -> Result<Vec<Cell>, RadarDecodeError> {
     let mut cells = Vec::with_capacity(9);
 
-    for &byte in bytes.iter() {
-        let high = (byte >> 4) & 0x0F; // 4 bits de gauche
-        let low = byte & 0x0F; // 4 bits de droite
+    for index in 0..9 {
+        let byte = bytes[index / 2];
+        let bits = if index % 2 == 0 {
+            (byte >> 4) & 0x0F // 4 bits de gauche
+        } else {
+            byte & 0x0F // 4 bits de droite
+        };
 
-        cells.push(Cell::from_bits(high)?);
-        cells.push(Cell::from_bits(low)?);
+        cells.push(Cell::from_bits(bits).ok_or(RadarDecodeError::InvalidCell { index, bits })?);
     }
 
-    // Supprimer le padding final si nécessaire
-    if cells.len() > 9 {
-        cells.truncate(9);
+    let padding = bytes[4] & 0x0F;
+    if padding != 0 {
+        return Err(RadarDecodeError::NonZeroPadding {
+            section: Some(RadarSection::Cells),
+            bits: padding,
+        });
     }
 
     Ok(cells)
 }
 
-pub fn decode_radarview(encoded: &str) -> Result<RadarView, &'static str> {
-    let bytes = decode(encoded)?;
-
-    // for byte in &bytes {
-    //     print!("{:08b} \n", &byte);
-    // }
-
-    if bytes.len() != 11 {
-        return Err("Invalid radar view bytes length");
+pub fn decode_radarview(encoded: &str) -> Result<RadarView, RadarDecodeError> {
+    let actual = decoded_len(encoded.len()).ok_or(DecodeError::InvalidLength { length: encoded.len() })?;
+    if actual != 11 {
+        return Err(RadarDecodeError::InvalidLength { expected: 11, actual });
     }
+    // Décodée à chaque tick : pas d'allocation
+    let mut bytes = [0u8; 11];
+    // 15 caractères base64 portent 90 bits pour 88 utiles : les 2 derniers bits doivent être nuls,
+    // sinon `DecodeError::NonCanonicalTrailingBits` devient `NonZeroPadding`
+    decode_into(encoded, &mut bytes)?;
 
-    let horizontal = decode_walls(&bytes[0..3], 12)?;
-    let vertical = decode_walls(&bytes[3..6], 12)?;
-    let cells = decode_cells(&bytes[6..11])?;
+    let horizontal = decode_walls(&[bytes[0], bytes[1], bytes[2]], RadarSection::Horizontal)?;
+    let vertical = decode_walls(&[bytes[3], bytes[4], bytes[5]], RadarSection::Vertical)?;
+    let cells = decode_cells(&[bytes[6], bytes[7], bytes[8], bytes[9], bytes[10]])?;
 
     Ok(RadarView {
         horizontal,
@@ -297,17 +495,41 @@ pub fn encode_radarview(radar: &RadarView) -> Result<String, &'static str> {
     let vertical_bytes = encode_walls(&radar.vertical)?;
     let cell_bytes = encode_cells(&radar.cells)?;
 
-    let mut bytes = Vec::with_capacity(11);
-    bytes.extend_from_slice(&horizontal_bytes);
-    bytes.extend_from_slice(&vertical_bytes);
-    bytes.extend_from_slice(&cell_bytes);
+    let mut bytes = [0u8; 11];
+    bytes[..3].copy_from_slice(&horizontal_bytes);
+    bytes[3..6].copy_from_slice(&vertical_bytes);
+    bytes[6..].copy_from_slice(&cell_bytes);
 
     Ok(encode(&bytes))
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::{collection::vec, prelude::*};
+
     use super::*;
+    use crate::enums::Message;
+
+    fn wall_state() -> impl Strategy<Value = WallState> {
+        prop_oneof![Just(WallState::Undefined), Just(WallState::Open), Just(WallState::Wall)]
+    }
+
+    fn cell() -> impl Strategy<Value = Cell> {
+        let element = prop_oneof![Just(Element::None), Just(Element::Hint), Just(Element::Target)];
+        let entity = prop_oneof![Just(Entity::None), Just(Entity::Ally), Just(Entity::Enemy), Just(Entity::Monster)];
+        prop_oneof![
+            Just(Cell::Invalid),
+            (element, entity).prop_map(|(element, entity)| Cell::Valid { element, entity }),
+        ]
+    }
+
+    fn radar_view() -> impl Strategy<Value = RadarView> {
+        (vec(wall_state(), 12), vec(wall_state(), 12), vec(cell(), 9)).prop_map(|(horizontal, vertical, cells)| RadarView {
+            horizontal,
+            vertical,
+            cells,
+        })
+    }
 
     #[test]
     fn test_decode_encode() {
@@ -318,4 +540,203 @@ mod tests {
         print!("{:?}", radar);
         assert_eq!(encoded, re_encoded);
     }
+
+    #[test]
+    fn test_rotations_and_reflections() {
+        for encoded in ["ieysGjGO8papd/a", "QQQQvvvv9iasmpa"] {
+            let radar = decode_radarview(encoded).unwrap();
+
+            let mut rotated = radar.clone();
+            for _ in 0..4 {
+                rotated = rotated.rotate_clockwise();
+                // L'encodage d'une vue tournée doit redonner la même vue
+                let re_encoded = encode_radarview(&rotated).unwrap();
+                assert_eq!(decode_radarview(&re_encoded).unwrap(), rotated);
+            }
+            assert_eq!(rotated, radar);
+
+            assert_eq!(radar.rotate_clockwise().rotate_counter_clockwise(), radar);
+            assert_eq!(radar.rotate_clockwise().rotate_clockwise(), radar.rotate_half_turn());
+            assert_eq!(radar.rotate(-90), Some(radar.rotate_counter_clockwise()));
+            assert_eq!(radar.rotate(360), Some(radar.clone()));
+            assert_eq!(radar.reflect_horizontal().reflect_horizontal(), radar);
+            assert_eq!(radar.reflect_horizontal().reflect_vertical(), radar.rotate_half_turn());
+            assert_eq!(RadarView::from_grid(&radar.to_grid()), radar);
+        }
+    }
+
+    #[test]
+    fn test_rotate_rejects_partial_turns() {
+        let radar = decode_radarview("ieysGjGO8papd/a").unwrap();
+        assert_eq!(radar.rotate(45), None);
+        assert_eq!(radar.rotate(-135), None);
+        assert_eq!(radar.rotate(1), None);
+    }
+
+    #[test]
+    fn test_rotate_moves_walls_and_cells() {
+        // Tous les murs horizontaux fermés, les verticaux ouverts
+        let radar = decode_radarview("QQQQvvvv9iasmpa").unwrap();
+        let rotated = radar.rotate_clockwise();
+
+        assert!(rotated.horizontal.iter().all(|wall| *wall == WallState::Open));
+        assert!(rotated.vertical.iter().all(|wall| *wall == WallState::Wall));
+        // La case en bas à gauche passe en haut à gauche, celle du haut à gauche en haut à droite
+        assert_eq!(rotated.cells[0], radar.cells[6]);
+        assert_eq!(rotated.cells[2], radar.cells[0]);
+        assert_eq!(rotated.cells[4], radar.cells[4]);
+
+        let reflected = radar.reflect_horizontal();
+        assert_eq!(reflected.cells[0], radar.cells[2]);
+        assert_eq!(reflected.cells[3], radar.cells[5]);
+    }
+
+    #[test]
+    fn test_serde_uses_encoded_string() {
+        let radar = decode_radarview("ieysGjGO8papd/a").unwrap();
+        assert_eq!(serde_json::to_string(&radar).unwrap(), r#""ieysGjGO8papd/a""#);
+        assert_eq!(serde_json::from_str::<RadarView>(r#""ieysGjGO8papd/a""#).unwrap(), radar);
+
+        let error = serde_json::from_str::<RadarView>(r#""ieysGjGO8papd/b""#).unwrap_err();
+        assert!(error.to_string().contains("non-zero base64 padding bits"));
+
+        // La vue brute passe même si elle n'est pas décodable
+        let raw: RawRadarView = serde_json::from_str(r#""ieysGjGO8papd/b""#).unwrap();
+        assert!(raw.decode().is_err());
+        assert_eq!(RawRadarView::try_from(&radar).unwrap(), RawRadarView(String::from("ieysGjGO8papd/a")));
+
+        let message = r#"{"RadarView":"ieysGjGO8papd/b"}"#;
+        assert!(serde_json::from_str::<Message>(message).is_err());
+        assert_eq!(Message::raw_radar_view(message), Some(raw));
+        assert_eq!(Message::raw_radar_view(r#"{"Hint":{"Secret":1}}"#), None);
+    }
+
+    // Vue encodée à partir de 11 octets bruts
+    fn encoded(bytes: [u8; 11]) -> String {
+        encode(&bytes)
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let mut bytes = [0u8; 11];
+        bytes[2] = 0b1100_0000; // Premier mur horizontal à 3
+        assert_eq!(
+            decode_radarview(&encoded(bytes)),
+            Err(RadarDecodeError::InvalidWall { section: RadarSection::Horizontal, index: 0, bits: 3 })
+        );
+
+        let mut bytes = [0u8; 11];
+        bytes[3] = 0b0000_0011; // Dernier mur vertical à 3
+        assert_eq!(
+            decode_radarview(&encoded(bytes)),
+            Err(RadarDecodeError::InvalidWall { section: RadarSection::Vertical, index: 11, bits: 3 })
+        );
+
+        let mut bytes = [0u8; 11];
+        bytes[7] = 0b1100_0000; // Troisième case avec l'élément 0b11
+        assert_eq!(
+            decode_radarview(&encoded(bytes)),
+            Err(RadarDecodeError::InvalidCell { index: 2, bits: 0b1100 })
+        );
+
+        let mut bytes = [0u8; 11];
+        bytes[10] = 0b0000_0101; // Dixième quartet non nul
+        assert_eq!(
+            decode_radarview(&encoded(bytes)),
+            Err(RadarDecodeError::NonZeroPadding { section: Some(RadarSection::Cells), bits: 0b0101 })
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_bad_encodings() {
+        // Même octets que la vue de référence, mais bits de fin du dernier caractère non nuls
+        assert_eq!(
+            decode_radarview("ieysGjGO8papd/b"),
+            Err(RadarDecodeError::NonZeroPadding { section: None, bits: 1 })
+        );
+        assert_eq!(
+            decode_radarview("ieysGjGO8pap"),
+            Err(RadarDecodeError::InvalidLength { expected: 11, actual: 9 })
+        );
+        assert!(matches!(decode_radarview("ieysGjGO8papd@a"), Err(RadarDecodeError::Base64(_))));
+        assert_eq!(
+            decode_radarview("ieysGjGO8papd/b").unwrap_err().to_string(),
+            "non-zero base64 padding bits: 1"
+        );
+    }
+
+    #[test]
+    fn test_render_ascii() {
+        let radar = decode_radarview("ieysGjGO8papd/a").unwrap();
+        let expected = "\
+##+ +##
+##| |##
++-+ +##
+|   |##
++ +-+##
+| #####
++-+####
+";
+        assert_eq!(radar.render(RenderStyle::Ascii), expected);
+    }
+
+    #[test]
+    fn test_render_unicode() {
+        let radar = decode_radarview("ieysGjGO8papd/a").unwrap();
+        let expected = "\
+░░╷ ╷░░
+░░│ │░░
+┌─┘ │░░
+│   │░░
+│ ╶─┘░░
+│ ░░░░░
+└─╴░░░░
+";
+        assert_eq!(radar.render(RenderStyle::Unicode), expected);
+    }
+
+    #[test]
+    fn test_render_elements_and_entities() {
+        // Murs horizontaux partout, aucun mur vertical
+        let radar = decode_radarview("QQQQvvvv9iasmpa").unwrap();
+        let expected = "\
++-+-+-+
+ # H G 
++-+-+-+
+   A E 
++-+-+-+
+ M   # 
++-+-+-+
+";
+        assert_eq!(radar.render(RenderStyle::Ascii), expected);
+        assert_eq!(radar.render(RenderStyle::Unicode).lines().next(), Some("╶─────╴"));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_radar_round_trip(radar in radar_view()) {
+            let encoded = encode_radarview(&radar).unwrap();
+            prop_assert_eq!(encoded.len(), 15);
+            prop_assert_eq!(decode_radarview(&encoded).unwrap(), radar.clone());
+
+            let json = serde_json::to_string(&Message::RadarView(radar.clone())).unwrap();
+            prop_assert_eq!(json, format!(r#"{{"RadarView":"{encoded}"}}"#));
+        }
+
+        #[test]
+        fn prop_rotations(radar in radar_view()) {
+            let turned = radar.rotate_clockwise();
+            prop_assert_eq!(turned.rotate_clockwise().rotate_clockwise().rotate_clockwise(), radar.clone());
+            prop_assert_eq!(turned.rotate_counter_clockwise(), radar.clone());
+            prop_assert_eq!(radar.reflect_vertical().reflect_vertical(), radar);
+        }
+
+        // Une chaîne quelconque est refusée ou décodée en une vue qui se ré-encode à l'identique
+        #[test]
+        fn prop_decode_radarview_is_canonical(text in "[a-zA-Z0-9+/]{15}|\\PC{0,20}") {
+            if let Ok(radar) = decode_radarview(&text) {
+                prop_assert_eq!(encode_radarview(&radar).unwrap(), text);
+            }
+        }
+    }
 }
@@ -0,0 +1,140 @@
+use std::{
+    cell::RefCell,
+    fs::File,
+    io::{self, BufRead, BufReader, LineWriter, Write},
+    path::Path,
+    sync::{Mutex, OnceLock},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+// Sens du message, du point de vue du binaire qui enregistre
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MessageDirection {
+    Sent,
+    Received,
+}
+
+// Une ligne du fichier d'enregistrement (format JSON, une ligne par message)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedMessage {
+    pub direction: MessageDirection,
+    pub timestamp_ms: u64, // Millisecondes depuis l'epoch UNIX
+    pub player: String,
+    pub message: serde_json::Value,
+}
+
+pub struct Recorder {
+    writer: Mutex<LineWriter<File>>,
+}
+
+impl Recorder {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        Ok(Self {
+            writer: Mutex::new(LineWriter::new(File::create(path)?)),
+        })
+    }
+
+    pub fn record(&self, player: &str, direction: MessageDirection, raw_message: &str) -> io::Result<()> {
+        // Un message qui n'est pas du JSON valide est conservé tel quel, c'est justement ce qu'on veut pouvoir rejouer
+        let message = serde_json::from_str(raw_message)
+            .unwrap_or_else(|_| serde_json::Value::String(raw_message.to_string()));
+
+        let entry = RecordedMessage {
+            direction,
+            timestamp_ms: now_ms(),
+            player: player.to_string(),
+            message,
+        };
+
+        let line = serde_json::to_string(&entry)?;
+        let mut writer = self.writer.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        writeln!(writer, "{line}")
+    }
+}
+
+static RECORDER: OnceLock<Recorder> = OnceLock::new();
+
+thread_local! {
+    // Nom du joueur (ou de l'équipe) qui utilise la connexion du thread courant
+    static PLAYER: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+// Active l'enregistrement de tous les messages envoyés et reçus par le processus
+pub fn start_recording(path: &Path) -> io::Result<()> {
+    let recorder = Recorder::create(path)?;
+    RECORDER
+        .set(recorder)
+        .map_err(|_| io::Error::new(io::ErrorKind::AlreadyExists, "Recording already started"))
+}
+
+// Associe les prochains messages du thread courant à un joueur
+pub fn set_player(name: &str) {
+    PLAYER.with(|player| *player.borrow_mut() = name.to_string());
+}
+
+// Appelé par les fonctions d'envoi et de lecture, ne fait rien si l'enregistrement n'est pas actif
+pub fn record(direction: MessageDirection, raw_message: &str) {
+    if RECORDER.get().is_some() {
+        PLAYER.with(|player| record_as(&player.borrow(), direction, raw_message));
+    }
+}
+
+// Comme `record`, pour un joueur donné (plusieurs joueurs par thread en asynchrone)
+pub fn record_as(player: &str, direction: MessageDirection, raw_message: &str) {
+    if let Some(recorder) = RECORDER.get() {
+        if let Err(err) = recorder.record(player, direction, raw_message) {
+            eprintln!("Impossible d'enregistrer le message: {err}");
+        }
+    }
+}
+
+pub fn read_recording(path: &Path) -> io::Result<Vec<RecordedMessage>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut messages = Vec::new();
+
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        messages.push(serde_json::from_str(&line)?);
+    }
+
+    Ok(messages)
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_read_back() {
+        let path = std::env::temp_dir().join(format!("recorder-test-{}.jsonl", std::process::id()));
+        let recorder = Recorder::create(&path).unwrap();
+
+        recorder
+            .record("Player-0", MessageDirection::Received, r#"{"RadarView":"ieysGjGO8papd/a"}"#)
+            .unwrap();
+        recorder
+            .record("Player-0", MessageDirection::Sent, "not json")
+            .unwrap();
+
+        let messages = read_recording(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].direction, MessageDirection::Received);
+        assert_eq!(messages[0].player, "Player-0");
+        assert_eq!(messages[0].message["RadarView"], "ieysGjGO8papd/a");
+        assert_eq!(messages[1].message, serde_json::Value::String(String::from("not json")));
+    }
+}
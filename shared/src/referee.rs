@@ -0,0 +1,528 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    enums::{Action, ActionError, Hint, Message},
+    maze::Maze,
+    radar_view::RadarView,
+    simulator::SimulationConfig,
+    structs::Position,
+    world::{MoveOutcome, World},
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TeamReport {
+    pub name: String,
+    pub moves: u64,                 // Déplacements réussis de toute l'équipe
+    pub moves_to_exit: Option<u64>, // Déplacements de l'équipe quand un joueur a atteint la sortie
+    pub wall_collisions: u64,
+    pub opponent_collisions: u64,
+    pub challenges: u64,
+    pub challenge_failures: u64,
+    #[serde(default)]
+    pub monster_catches: u64,
+}
+
+// Statistiques d'un joueur, aussi comptées dans le rapport de son équipe
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerReport {
+    pub team: String,
+    pub name: String,
+    pub actions: u64, // Actions jouées, déplacements refusés compris
+    pub moves: u64,
+    pub wall_collisions: u64,
+    pub opponent_collisions: u64,
+    pub challenges: u64,
+    pub challenge_failures: u64,
+    pub monster_catches: u64,
+    pub exited: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Stat {
+    Move,
+    WallCollision,
+    OpponentCollision,
+    Challenge,
+    ChallengeFailure,
+    MonsterCatch,
+}
+
+// Évènements de la partie, pour les spectateurs du serveur de test
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameEvent {
+    ChallengeStarted { player: usize, modulo: u64 },
+    ChallengeSolved { player: usize },
+    ChallengeFailed { player: usize },
+    CaughtByMonster { player: usize },
+    ReachedExit { player: usize },
+}
+
+// Graine du flux aléatoire `label` d'une partie, stable d'une exécution et d'une version de Rust
+// à l'autre (FNV-1a)
+pub fn stream_seed(seed: u64, label: &str) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325 ^ seed;
+    for byte in label.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+// Arbitre d'une partie : applique les règles du monde aux actions des joueurs, lance les challenges
+// et tient les statistiques par équipe. Partagé par le simulateur et les serveurs
+pub struct Referee {
+    pub world: World,
+    pub reports: Vec<TeamReport>,
+    pub player_reports: Vec<PlayerReport>,
+    rng: StdRng,
+    seed: u64,
+    player_rngs: Vec<StdRng>, // Secrets et challenges de chaque joueur
+    challenge_every: Option<u64>, // Un challenge tous les N déplacements d'un joueur
+    moves_since_challenge: Vec<u64>,
+    actions_since_monsters: usize,
+    grid_size_hint: bool,
+    secret_every: Option<u64>,
+    events: Option<Vec<GameEvent>>, // Journal des évènements, seulement si quelqu'un le lit
+}
+
+impl Referee {
+    // Le labyrinthe, les monstres, les indices, les secrets et les challenges dépendent uniquement
+    // de `config.seed`. Chaque joueur a son propre flux aléatoire, tiré de la graine et de son nom :
+    // ses secrets et challenges ne dépendent pas de l'ordre d'arrivée des joueurs
+    pub fn new(config: &SimulationConfig) -> Self {
+        let mut rng = StdRng::seed_from_u64(config.seed);
+        let mut world = World::new(Maze::generate(config.width, config.height, &mut rng));
+        world.catch_penalty = config.catch_penalty;
+
+        // Les monstres apparaissent ailleurs qu'au départ et qu'à la sortie, s'il reste une case
+        let reserved = if world.maze.start == world.maze.exit { 1 } else { 2 };
+        let free_cells = (config.width * config.height).saturating_sub(reserved);
+        if free_cells > 0 {
+            for &behavior in &config.monsters {
+                let position = loop {
+                    let position = Position {
+                        x: rng.random_range(0..config.width),
+                        y: rng.random_range(0..config.height),
+                    };
+                    if position != world.maze.start && position != world.maze.exit {
+                        break position;
+                    }
+                };
+                world.add_monster(behavior, position);
+            }
+        }
+
+        // Les cases indice aussi, sans doublon
+        while world.hints.len() < config.hints.min(free_cells) {
+            let position = Position {
+                x: rng.random_range(0..config.width),
+                y: rng.random_range(0..config.height),
+            };
+            if position != world.maze.start && position != world.maze.exit {
+                world.add_hint(position);
+            }
+        }
+
+        Self {
+            world,
+            reports: Vec::new(),
+            player_reports: Vec::new(),
+            rng,
+            seed: config.seed,
+            player_rngs: Vec::new(),
+            challenge_every: config.challenge_every,
+            moves_since_challenge: Vec::new(),
+            actions_since_monsters: 0,
+            grid_size_hint: config.grid_size_hint,
+            secret_every: config.secret_every,
+            events: None,
+        }
+    }
+
+    // Active le journal des évènements, vidé par `take_events`
+    pub fn record_events(&mut self) {
+        self.events.get_or_insert_with(Vec::new);
+    }
+
+    pub fn take_events(&mut self) -> Vec<GameEvent> {
+        self.events.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    fn log(&mut self, event: GameEvent) {
+        if let Some(events) = &mut self.events {
+            events.push(event);
+        }
+    }
+
+    pub fn add_team(&mut self, name: &str) -> usize {
+        self.reports.push(TeamReport {
+            name: name.to_string(),
+            ..TeamReport::default()
+        });
+        self.world.add_team(name)
+    }
+
+    // Nouveau joueur sur la case de départ, avec un secret tiré au hasard
+    pub fn add_player(&mut self, team: usize, name: &str) -> usize {
+        let label = format!("player/{}/{name}", self.reports[team].name);
+        let mut rng = StdRng::seed_from_u64(stream_seed(self.seed, &label));
+        let secret = rng.random_range(0..1_000_000);
+        self.player_rngs.push(rng);
+        self.moves_since_challenge.push(0);
+        self.player_reports.push(PlayerReport {
+            team: self.reports[team].name.clone(),
+            name: name.to_string(),
+            ..PlayerReport::default()
+        });
+        self.world.add_player(team, name, secret)
+    }
+
+    pub fn team_finished(&self, team: usize) -> bool {
+        self.reports[team].moves_to_exit.is_some()
+    }
+
+    pub fn all_finished(&self) -> bool {
+        self.reports.iter().all(|report| report.moves_to_exit.is_some())
+    }
+
+    // Indices à envoyer au joueur au début de son tour `turn` (à partir de 0) : son secret et la
+    // taille du labyrinthe au premier tour, puis un nouveau secret tous les `secret_every` tours
+    pub fn scheduled_hints(&mut self, player: usize, turn: u64) -> Vec<Message> {
+        let mut hints = Vec::new();
+        if turn == 0 {
+            hints.push(Message::Hint(Hint::Secret(self.world.players[player].secret)));
+            if self.grid_size_hint {
+                hints.push(Message::Hint(Hint::GridSize {
+                    columns: self.world.maze.width as u32,
+                    rows: self.world.maze.height as u32,
+                }));
+            }
+        } else if self.secret_every.is_some_and(|every| turn.is_multiple_of(every.max(1))) {
+            let secret = self.player_rngs[player].random_range(0..1_000_000);
+            self.world.players[player].secret = secret;
+            hints.push(Message::Hint(Hint::Secret(secret)));
+        }
+        hints
+    }
+
+    // Pas de vue radar tant que le joueur n'a pas résolu son challenge
+    pub fn radar_view(&self, player: usize) -> Option<RadarView> {
+        self.world.players[player]
+            .challenge
+            .is_none()
+            .then(|| self.world.radar_view(player))
+    }
+
+    // Applique l'action d'un joueur, retourne les messages à lui renvoyer (erreur, challenge).
+    // Les monstres se déplacent une fois que chaque joueur encore en jeu a joué en moyenne une action
+    pub fn apply(&mut self, player: usize, action: Action) -> Vec<Message> {
+        self.player_reports[player].actions += 1;
+        let replies = self.apply_action(player, action);
+
+        self.actions_since_monsters += 1;
+        let active = self.world.players.iter().filter(|player| !player.exited).count();
+        if self.actions_since_monsters >= active.max(1) {
+            self.actions_since_monsters = 0;
+            self.move_monsters();
+        }
+        replies
+    }
+
+    // Lance un challenge pour ce joueur, retourne le message à lui envoyer
+    pub fn start_challenge(&mut self, player: usize) -> Message {
+        self.moves_since_challenge[player] = 0;
+        self.count(player, Stat::Challenge);
+        let modulo = self.player_rngs[player].random_range(2..10_000);
+        self.log(GameEvent::ChallengeStarted { player, modulo });
+        Message::Challenge(self.world.start_challenge(player, modulo))
+    }
+
+    pub fn move_monsters(&mut self) {
+        for caught in self.world.move_monsters(&mut self.rng) {
+            self.count(caught, Stat::MonsterCatch);
+            self.log(GameEvent::CaughtByMonster { player: caught });
+        }
+    }
+
+    fn count(&mut self, player: usize, stat: Stat) {
+        let team = &mut self.reports[self.world.players[player].team];
+        let player = &mut self.player_reports[player];
+        let (team, player) = match stat {
+            Stat::Move => (&mut team.moves, &mut player.moves),
+            Stat::WallCollision => (&mut team.wall_collisions, &mut player.wall_collisions),
+            Stat::OpponentCollision => (&mut team.opponent_collisions, &mut player.opponent_collisions),
+            Stat::Challenge => (&mut team.challenges, &mut player.challenges),
+            Stat::ChallengeFailure => (&mut team.challenge_failures, &mut player.challenge_failures),
+            Stat::MonsterCatch => (&mut team.monster_catches, &mut player.monster_catches),
+        };
+        *team += 1;
+        *player += 1;
+    }
+
+    fn apply_action(&mut self, player: usize, action: Action) -> Vec<Message> {
+        let (replies, event) = self.judge(player, action);
+        if let Some(event) = event {
+            self.log(event);
+        }
+        replies
+    }
+
+    fn judge(&mut self, player: usize, action: Action) -> (Vec<Message>, Option<GameEvent>) {
+        match action {
+            Action::MoveTo(direction) => match self.world.move_player(player, direction) {
+                Ok(MoveOutcome::Frozen) => (Vec::new(), None),
+                Ok(MoveOutcome::Caught) => {
+                    self.count(player, Stat::Move);
+                    self.count(player, Stat::MonsterCatch);
+                    (Vec::new(), Some(GameEvent::CaughtByMonster { player }))
+                }
+                Ok(MoveOutcome::ReachedExit) => {
+                    self.count(player, Stat::Move);
+                    self.player_reports[player].exited = true;
+                    let report = &mut self.reports[self.world.players[player].team];
+                    report.moves_to_exit.get_or_insert(report.moves);
+                    (Vec::new(), Some(GameEvent::ReachedExit { player }))
+                }
+                Ok(MoveOutcome::Moved) => {
+                    self.count(player, Stat::Move);
+                    let mut replies = Vec::new();
+
+                    // Une case indice donne la direction de la sortie
+                    if self.world.is_hint(self.world.players[player].position) {
+                        let angle = self.world.compass_angle(player);
+                        replies.push(Message::Hint(Hint::RelativeCompass { angle }));
+                    }
+
+                    self.moves_since_challenge[player] += 1;
+                    if self
+                        .challenge_every
+                        .is_some_and(|every| self.moves_since_challenge[player] >= every)
+                    {
+                        replies.push(self.start_challenge(player));
+                    }
+                    (replies, None)
+                }
+                Err(error) => {
+                    match error {
+                        ActionError::CannotPassThroughWall => self.count(player, Stat::WallCollision),
+                        ActionError::CannotPassThroughOpponent => self.count(player, Stat::OpponentCollision),
+                        _ => (),
+                    }
+                    (vec![Message::ActionError(error)], None)
+                }
+            },
+
+            Action::SolveChallenge { answer } => match self.world.solve_challenge(player, &answer) {
+                Ok(()) => (Vec::new(), Some(GameEvent::ChallengeSolved { player })),
+                Err(error) => {
+                    let mut event = None;
+                    if error == ActionError::InvalidChallengeSolution {
+                        self.count(player, Stat::ChallengeFailure);
+                        event = Some(GameEvent::ChallengeFailed { player });
+                    }
+                    (vec![Message::ActionError(error)], event)
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{enums::Challenge, game_engine::Direction, maze::Heading, world::MonsterBehavior};
+
+    // Première case accessible depuis le départ, et l'orientation pour y aller
+    fn first_step(referee: &Referee) -> (Heading, Position) {
+        let maze = &referee.world.maze;
+        [Heading::North, Heading::East, Heading::South, Heading::West]
+            .into_iter()
+            .find_map(|heading| maze.step(maze.start, heading).map(|target| (heading, target)))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_reports_collisions_and_challenges() {
+        let config = SimulationConfig {
+            width: 4,
+            height: 4,
+            challenge_every: Some(1),
+            seed: 2,
+            ..SimulationConfig::default()
+        };
+        let mut referee = Referee::new(&config);
+        referee.record_events();
+        let blue = referee.add_team("blue");
+        let red = referee.add_team("red");
+        let runner = referee.add_player(blue, "blue-0");
+        let blocker = referee.add_player(red, "red-0");
+
+        // Le premier déplacement possible du coureur, bloqué par un adversaire
+        let (heading, target) = first_step(&referee);
+        referee.world.players[blocker].position = target;
+        referee.world.players[runner].heading = heading;
+
+        assert_eq!(
+            referee.apply(runner, Action::MoveTo(Direction::Front)).len(),
+            1
+        );
+        assert_eq!(referee.reports[blue].opponent_collisions, 1);
+
+        // Une fois la voie libre, chaque déplacement lance un challenge
+        referee.world.players[blocker].position = Position { x: usize::MAX, y: usize::MAX };
+        let replies = referee.apply(runner, Action::MoveTo(Direction::Front));
+        let Some(Message::Challenge(Challenge::SecretSumModulo(modulo))) = replies.first() else {
+            panic!("{replies:?}");
+        };
+        assert!(referee.radar_view(runner).is_none());
+
+        referee.apply(runner, Action::SolveChallenge { answer: String::from("not a number") });
+        assert_eq!(referee.reports[blue].challenge_failures, 1);
+
+        let answer = referee.world.expected_answer(blue, *modulo).to_string();
+        assert!(referee.apply(runner, Action::SolveChallenge { answer }).is_empty());
+        assert!(referee.radar_view(runner).is_some());
+        assert_eq!(referee.reports[blue].moves, 1);
+        assert_eq!(referee.reports[blue].challenges, 1);
+        assert!(!referee.team_finished(blue));
+
+        // Les mêmes statistiques par joueur
+        let runner_report = &referee.player_reports[runner];
+        assert_eq!((runner_report.team.as_str(), runner_report.actions), ("blue", 4));
+        assert_eq!(
+            (runner_report.moves, runner_report.opponent_collisions, runner_report.challenge_failures),
+            (1, 1, 1)
+        );
+        assert_eq!(referee.player_reports[blocker], PlayerReport {
+            team: String::from("red"),
+            name: String::from("red-0"),
+            ..PlayerReport::default()
+        });
+        assert_eq!(
+            referee.take_events(),
+            vec![
+                GameEvent::ChallengeStarted { player: runner, modulo: *modulo },
+                GameEvent::ChallengeFailed { player: runner },
+                GameEvent::ChallengeSolved { player: runner },
+            ]
+        );
+        assert!(referee.take_events().is_empty());
+    }
+
+    #[test]
+    fn test_tiny_mazes_have_no_room_for_monsters() {
+        for (width, height) in [(1, 1), (1, 2), (2, 1)] {
+            let config = SimulationConfig {
+                width,
+                height,
+                monsters: vec![MonsterBehavior::Chase],
+                hints: 3,
+                ..SimulationConfig::default()
+            };
+            let world = Referee::new(&config).world;
+            let maze = &world.maze;
+            let free = width * height - if maze.start == maze.exit { 1 } else { 2 };
+            assert_eq!(world.monsters.len(), free.min(1), "{width}x{height}");
+            assert_eq!(world.hints.len(), free, "{width}x{height}");
+        }
+    }
+
+    #[test]
+    fn test_monsters_move_once_per_round() {
+        let config = SimulationConfig {
+            width: 5,
+            height: 5,
+            monsters: vec![MonsterBehavior::Patrol, MonsterBehavior::Chase, MonsterBehavior::RandomWalk],
+            ..SimulationConfig::default()
+        };
+        let mut referee = Referee::new(&config);
+        let team = referee.add_team("team");
+        let first = referee.add_player(team, "Player-0");
+        let second = referee.add_player(team, "Player-1");
+
+        let maze = &referee.world.maze;
+        let positions = |referee: &Referee| -> Vec<Position> {
+            referee.world.monsters.iter().map(|monster| monster.position).collect()
+        };
+        let placed = positions(&referee);
+        assert_eq!(placed.len(), 3);
+        assert!(placed.iter().all(|&position| position != maze.start && position != maze.exit));
+
+        // Deux joueurs en jeu : les monstres bougent toutes les deux actions
+        referee.apply(first, Action::SolveChallenge { answer: String::new() });
+        assert_eq!(positions(&referee), placed);
+        referee.apply(second, Action::SolveChallenge { answer: String::new() });
+        assert_ne!(positions(&referee), placed);
+    }
+
+    #[test]
+    fn test_scheduled_hints_and_compass() {
+        let config = SimulationConfig {
+            width: 4,
+            height: 4,
+            hints: 3,
+            secret_every: Some(2),
+            ..SimulationConfig::default()
+        };
+        let mut referee = Referee::new(&config);
+        let team = referee.add_team("team");
+        let player = referee.add_player(team, "Player-0");
+        assert_eq!(referee.world.hints.len(), 3);
+
+        let first = referee.scheduled_hints(player, 0);
+        assert!(matches!(
+            first.as_slice(),
+            [Message::Hint(Hint::Secret(_)), Message::Hint(Hint::GridSize { columns: 4, rows: 4 })]
+        ));
+        assert!(referee.scheduled_hints(player, 1).is_empty());
+
+        // Un nouveau secret, que le challenge utilisera
+        let renewed = referee.scheduled_hints(player, 2);
+        let [Message::Hint(Hint::Secret(secret))] = renewed.as_slice() else {
+            panic!("{renewed:?}");
+        };
+        assert_eq!(*secret, referee.world.players[player].secret);
+
+        // Entrer sur une case indice donne la direction de la sortie
+        let (heading, target) = first_step(&referee);
+        referee.world.add_hint(target);
+        referee.world.players[player].heading = heading;
+        let replies = referee.apply(player, Action::MoveTo(Direction::Front));
+        let expected = referee.world.compass_angle(player);
+        assert!(matches!(
+            replies.as_slice(),
+            [Message::Hint(Hint::RelativeCompass { angle })] if *angle == expected
+        ));
+    }
+
+    #[test]
+    fn test_players_do_not_depend_on_arrival_order() {
+        let config = SimulationConfig {
+            seed: 9,
+            ..SimulationConfig::default()
+        };
+        let draws = |names: [&str; 2]| -> Vec<(String, u64, Message)> {
+            let mut referee = Referee::new(&config);
+            let team = referee.add_team("team");
+            let mut draws: Vec<(String, u64, Message)> = names
+                .into_iter()
+                .map(|name| {
+                    let player = referee.add_player(team, name);
+                    let challenge = referee.start_challenge(player);
+                    (name.to_string(), referee.world.players[player].secret, challenge)
+                })
+                .collect();
+            draws.sort_by(|a, b| a.0.cmp(&b.0));
+            draws
+        };
+
+        let (first, second) = (draws(["Player-0", "Player-1"]), draws(["Player-1", "Player-0"]));
+        for ((name, secret, challenge), (other_name, other_secret, other_challenge)) in first.iter().zip(&second) {
+            assert_eq!((name, secret), (other_name, other_secret));
+            assert_eq!(format!("{challenge:?}"), format!("{other_challenge:?}"));
+        }
+        assert_ne!(first[0].1, first[1].1);
+        assert_ne!(stream_seed(1, "tokens"), stream_seed(2, "tokens"));
+    }
+}
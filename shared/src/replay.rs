@@ -0,0 +1,176 @@
+use std::collections::{HashMap, VecDeque};
+
+use serde_json::Value;
+
+use crate::{
+    enums::{Incoming, Message},
+    functions::handle_message,
+    game_engine::{Direction, GameState, GlobalMap, Player},
+    recorder::{MessageDirection, RecordedMessage},
+};
+
+// Écart entre ce que le client a envoyé pendant la partie et ce que le moteur produit aujourd'hui
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    pub index: usize, // Position du message dans l'enregistrement
+    pub player: String,
+    pub recorded: Option<Value>, // Message envoyé pendant la partie
+    pub replayed: Option<Value>, // Message produit par le rejeu
+}
+
+#[derive(Debug, Default)]
+pub struct ReplayReport {
+    pub received: usize,     // Messages du serveur rejoués dans le moteur
+    pub checked: usize,      // Actions du client comparées
+    pub skipped: Vec<usize>, // Messages illisibles (JSON inconnu ou vue radar invalide)
+    pub divergences: Vec<Divergence>,
+}
+
+impl ReplayReport {
+    pub fn is_identical(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+
+// Les échanges d'enregistrement ne passent pas par le moteur de jeu
+fn is_registration(message: &Message) -> bool {
+    matches!(
+        message,
+        Message::RegisterTeam(_)
+            | Message::RegisterTeamResult(_)
+            | Message::SubscribePlayer(_)
+            | Message::SubscribePlayerResult(_)
+    )
+}
+
+// Rejoue un enregistrement côté client : les messages reçus sont passés au moteur
+// (carte, indices, challenges) avec un état partagé par toute l'équipe, comme dans le client,
+// et les actions produites sont comparées aux actions réellement envoyées
+pub fn replay(messages: &[RecordedMessage]) -> ReplayReport {
+    let mut report = ReplayReport::default();
+    let mut game_state = GameState::new();
+    let mut map = GlobalMap::new();
+    let mut players: HashMap<String, Player> = HashMap::new();
+    let mut pending: HashMap<String, VecDeque<Value>> = HashMap::new();
+
+    for (index, recorded) in messages.iter().enumerate() {
+        // Comme en partie, un message inconnu de cette version (ou mal formé) est ignoré
+        let Ok(Incoming::Known(message)) = Incoming::from_value(recorded.message.clone()) else {
+            report.skipped.push(index);
+            continue;
+        };
+        if is_registration(&message) {
+            continue;
+        }
+
+        match recorded.direction {
+            MessageDirection::Received => {
+                let queue = pending.entry(recorded.player.clone()).or_default();
+
+                // Le client répond avant de lire le message suivant : ce qui reste n'a jamais été envoyé
+                for replayed in queue.drain(..) {
+                    report.divergences.push(Divergence {
+                        index,
+                        player: recorded.player.clone(),
+                        recorded: None,
+                        replayed: Some(replayed),
+                    });
+                }
+
+                let player = players.entry(recorded.player.clone()).or_insert_with(|| Player {
+                    name: recorded.player.clone(),
+                    position: (0, 0),
+                    secret: Some(0),
+                    direction: Direction::Front,
+                });
+
+                let actions = handle_message(player, &message, &mut game_state, &mut map);
+                report.received += 1;
+                queue.extend(actions.iter().filter_map(|action| serde_json::to_value(action).ok()));
+            }
+
+            MessageDirection::Sent => {
+                report.checked += 1;
+                let replayed = pending.entry(recorded.player.clone()).or_default().pop_front();
+
+                if replayed.as_ref() != Some(&recorded.message) {
+                    report.divergences.push(Divergence {
+                        index,
+                        player: recorded.player.clone(),
+                        recorded: Some(recorded.message.clone()),
+                        replayed,
+                    });
+                }
+            }
+        }
+    }
+
+    // Actions produites à la fin de l'enregistrement mais jamais envoyées
+    let mut leftovers: Vec<_> = pending.into_iter().collect();
+    leftovers.sort_by(|a, b| a.0.cmp(&b.0));
+    for (player, queue) in leftovers {
+        for replayed in queue {
+            report.divergences.push(Divergence {
+                index: messages.len(),
+                player: player.clone(),
+                recorded: None,
+                replayed: Some(replayed),
+            });
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn entry(direction: MessageDirection, player: &str, message: Value) -> RecordedMessage {
+        RecordedMessage {
+            direction,
+            timestamp_ms: 0,
+            player: player.to_string(),
+            message,
+        }
+    }
+
+    fn match_recording() -> Vec<RecordedMessage> {
+        vec![
+            entry(MessageDirection::Sent, "Player-0", json!({"SubscribePlayer": {"name": "Player-0", "registration_token": "token"}})),
+            entry(MessageDirection::Received, "Player-0", json!({"SubscribePlayerResult": "Ok"})),
+            entry(MessageDirection::Received, "Player-0", json!({"Hint": {"Secret": 20}})),
+            entry(MessageDirection::Received, "Player-1", json!({"Hint": {"Secret": 22}})),
+            entry(MessageDirection::Received, "Player-0", json!({"Challenge": {"SecretSumModulo": 10}})),
+            entry(MessageDirection::Sent, "Player-0", json!({"Action": {"SolveChallenge": {"answer": "2"}}})),
+            entry(MessageDirection::Received, "Player-0", json!({"RadarView": "ieysGjGO8papd/a"})),
+            entry(MessageDirection::Sent, "Player-0", json!({"Action": {"MoveTo": "Front"}})),
+        ]
+    }
+
+    #[test]
+    fn test_replay_identical() {
+        let report = replay(&match_recording());
+
+        assert!(report.is_identical(), "{:?}", report.divergences);
+        assert_eq!(report.received, 4);
+        assert_eq!(report.checked, 2);
+    }
+
+    #[test]
+    fn test_replay_detects_divergence() {
+        let mut recording = match_recording();
+        recording[7].message = json!({"Action": {"MoveTo": "Back"}});
+        recording.push(entry(MessageDirection::Received, "Player-0", json!({"ActionError": "InvalidChallengeSolution"})));
+
+        let report = replay(&recording);
+
+        assert_eq!(report.divergences.len(), 2);
+        assert_eq!(report.divergences[0].index, 7);
+        assert_eq!(report.divergences[0].replayed, Some(json!({"Action": {"MoveTo": "Front"}})));
+        // La nouvelle réponse au challenge n'a jamais été envoyée
+        assert_eq!(report.divergences[1].recorded, None);
+    }
+}
@@ -0,0 +1,139 @@
+use std::{
+    io,
+    net::{Shutdown as StreamShutdown, TcpStream},
+    sync::{Mutex, MutexGuard},
+};
+
+// Raison de l'arrêt du client, la première raison signalée est conservée
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShutdownReason {
+    Interrupted,   // Ctrl-C (SIGINT)
+    GameOver,      // Le serveur a fermé la connexion, la partie est terminée
+    Fatal(String), // Erreur réseau ou protocole irrécupérable
+}
+
+impl ShutdownReason {
+    // Le serveur coupe la connexion des joueurs à la fin de la partie,
+    // toute autre erreur d'entrée/sortie est considérée comme fatale
+    pub fn from_io_error(error: &io::Error) -> Self {
+        match error.kind() {
+            io::ErrorKind::UnexpectedEof
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::BrokenPipe => ShutdownReason::GameOver,
+            _ => ShutdownReason::Fatal(error.to_string()),
+        }
+    }
+
+    // Code de sortie du processus associé à la raison de l'arrêt
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ShutdownReason::GameOver => 0,
+            ShutdownReason::Fatal(_) => 1,
+            ShutdownReason::Interrupted => 130, // Convention shell : 128 + SIGINT
+        }
+    }
+}
+
+// Signal d'arrêt partagé par tous les threads joueurs
+#[derive(Debug, Default)]
+pub struct Shutdown {
+    reason: Mutex<Option<ShutdownReason>>,
+    streams: Mutex<Vec<TcpStream>>,
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Enregistre une connexion à fermer lors de l'arrêt, pour débloquer les lectures en cours.
+    // `register_stream` et `trigger` prennent d'abord le verrou des connexions : une connexion
+    // est soit fermée par `trigger`, soit fermée ici si l'arrêt est déjà demandé
+    pub fn register_stream(&self, stream: &TcpStream) {
+        if let Ok(clone) = stream.try_clone() {
+            let mut streams = lock(&self.streams);
+            if self.is_triggered() {
+                let _ = clone.shutdown(StreamShutdown::Both);
+                return;
+            }
+            streams.push(clone);
+        }
+    }
+
+    // Déclenche l'arrêt. Retourne false si l'arrêt avait déjà été déclenché
+    pub fn trigger(&self, reason: ShutdownReason) -> bool {
+        let mut streams = lock(&self.streams);
+        {
+            let mut current = lock(&self.reason);
+            if current.is_some() {
+                return false;
+            }
+            *current = Some(reason);
+        }
+
+        // Fermer les connexions réveille les threads bloqués sur read_exact
+        for stream in streams.drain(..) {
+            let _ = stream.shutdown(StreamShutdown::Both);
+        }
+        true
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        lock(&self.reason).is_some()
+    }
+
+    pub fn reason(&self) -> Option<ShutdownReason> {
+        lock(&self.reason).clone()
+    }
+}
+
+// Un thread qui panique ne doit pas empêcher les autres de s'arrêter proprement
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Read, net::TcpListener, thread};
+
+    use super::*;
+
+    #[test]
+    fn test_first_reason_wins() {
+        let shutdown = Shutdown::new();
+        assert!(!shutdown.is_triggered());
+
+        assert!(shutdown.trigger(ShutdownReason::Interrupted));
+        assert!(!shutdown.trigger(ShutdownReason::GameOver));
+
+        assert_eq!(shutdown.reason(), Some(ShutdownReason::Interrupted));
+        assert_eq!(shutdown.reason().unwrap().exit_code(), 130);
+    }
+
+    #[test]
+    fn test_streams_are_closed_on_trigger() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = thread::spawn(move || (0..2).map(|_| listener.accept().unwrap().0).collect::<Vec<_>>());
+        let (mut before, mut after) = (TcpStream::connect(address).unwrap(), TcpStream::connect(address).unwrap());
+
+        // Enregistrée avant ou après l'arrêt, la connexion est fermée et la lecture se termine
+        let shutdown = Shutdown::new();
+        shutdown.register_stream(&before);
+        assert!(shutdown.trigger(ShutdownReason::Interrupted));
+        shutdown.register_stream(&after);
+        assert_eq!(before.read(&mut [0]).unwrap(), 0);
+        assert_eq!(after.read(&mut [0]).unwrap(), 0);
+        drop(server.join().unwrap());
+    }
+
+    #[test]
+    fn test_reason_from_io_error() {
+        let closed = io::Error::from(io::ErrorKind::UnexpectedEof);
+        assert_eq!(ShutdownReason::from_io_error(&closed), ShutdownReason::GameOver);
+
+        let invalid = io::Error::new(io::ErrorKind::InvalidData, "bad radar view");
+        assert_eq!(ShutdownReason::from_io_error(&invalid).exit_code(), 1);
+    }
+}
@@ -0,0 +1,389 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    enums::Message,
+    functions::{get_arg_value, handle_message},
+    game_engine::{Direction, GameState, GlobalMap, Player},
+    referee::Referee,
+    world::{CatchPenalty, MonsterBehavior, World},
+};
+
+pub use crate::referee::TeamReport;
+
+// Stratégie d'une équipe : reçoit les messages du serveur destinés à l'un de ses joueurs
+// (`player` est l'index du joueur dans l'équipe) et retourne les messages à renvoyer
+pub trait Strategy {
+    fn handle(&mut self, player: usize, message: &Message) -> Vec<Message>;
+}
+
+// Le comportement du client : une carte et un état de jeu partagés par toute l'équipe
+pub struct ClientStrategy {
+    players: Vec<Player>,
+    game_state: GameState,
+    map: GlobalMap,
+}
+
+impl ClientStrategy {
+    pub fn new(players: usize) -> Self {
+        Self {
+            players: (0..players)
+                .map(|i| Player {
+                    name: format!("Player-{}", i),
+                    position: (0, 0),
+                    secret: Some(0),
+                    direction: Direction::Front,
+                })
+                .collect(),
+            game_state: GameState::new(),
+            map: GlobalMap::new(),
+        }
+    }
+}
+
+impl Strategy for ClientStrategy {
+    fn handle(&mut self, player: usize, message: &Message) -> Vec<Message> {
+        handle_message(&mut self.players[player], message, &mut self.game_state, &mut self.map)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SimulationConfig {
+    pub width: usize,
+    pub height: usize,
+    pub players_per_team: usize,
+    pub max_turns: u64,
+    pub challenge_every: Option<u64>, // Un challenge tous les N déplacements d'un joueur
+    pub seed: u64,
+    pub monsters: Vec<MonsterBehavior>, // Un monstre par comportement, placé au hasard
+    pub catch_penalty: CatchPenalty,
+    pub hints: usize, // Nombre de cases indice placées au hasard
+    pub grid_size_hint: bool, // Envoie la taille du labyrinthe au premier tour
+    pub secret_every: Option<u64>, // Nouveau secret tous les N tours d'un joueur
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            width: 10,
+            height: 10,
+            players_per_team: 3,
+            max_turns: 1000,
+            challenge_every: None,
+            seed: 0,
+            monsters: Vec::new(),
+            catch_penalty: CatchPenalty::default(),
+            hints: 0,
+            grid_size_hint: true,
+            secret_every: None,
+        }
+    }
+}
+
+impl SimulationConfig {
+    // Options communes aux serveurs de test, les options absentes gardent leur valeur par défaut.
+    // Monstres: `--monsters random,patrol,chase`, pénalité: `--penalty respawn|freeze:<tours>`,
+    // indices: `--hints <cases>`, `--secret-every <tours>`, `--no-grid-size`
+    pub fn from_args(args: &[String]) -> Result<SimulationConfig, String> {
+        let defaults = SimulationConfig::default();
+        let number = |flag: &str| -> Result<Option<u64>, String> {
+            get_arg_value(args, flag)
+                .map(|value| value.parse().map_err(|_| format!("invalid value for {flag}: {value}")))
+                .transpose()
+        };
+        // Un labyrinthe a au moins une case de large et de haut
+        let dimension = |flag: &str, default: usize| match number(flag)? {
+            Some(0) => Err(format!("invalid value for {flag}: 0")),
+            value => Ok(value.map_or(default, |value| value as usize)),
+        };
+        // Le protocole envoie `expected_players` sur un octet
+        let players_per_team = match number("--players")? {
+            Some(players) => u8::try_from(players)
+                .map_err(|_| format!("invalid value for --players: {players}, at most {}", u8::MAX))?
+                as usize,
+            None => defaults.players_per_team,
+        };
+
+        Ok(SimulationConfig {
+            width: dimension("--width", defaults.width)?,
+            height: dimension("--height", defaults.height)?,
+            players_per_team,
+            max_turns: number("--max-turns")?.unwrap_or(defaults.max_turns),
+            challenge_every: number("--challenge-every")?,
+            seed: number("--seed")?.unwrap_or(defaults.seed),
+            monsters: get_arg_value(args, "--monsters")
+                .map_or(Ok(Vec::new()), |spec| spec.split(',').map(str::parse).collect())
+                .map_err(|err| format!("--monsters: {err}"))?,
+            catch_penalty: get_arg_value(args, "--penalty")
+                .map_or(Ok(defaults.catch_penalty), |penalty| penalty.parse())
+                .map_err(|err| format!("--penalty: {err}"))?,
+            hints: number("--hints")?.map_or(defaults.hints, |hints| hints as usize),
+            grid_size_hint: !args.iter().any(|arg| arg == "--no-grid-size"),
+            secret_every: number("--secret-every")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimulationReport {
+    pub turns: u64,
+    pub shortest_path: usize, // Distance du départ à la sortie, pour comparer les équipes
+    pub teams: Vec<TeamReport>,
+}
+
+// Partie complète sans réseau : le simulateur joue le rôle du serveur
+pub struct Simulator {
+    config: SimulationConfig,
+    referee: Referee,
+    strategies: Vec<Box<dyn Strategy>>,
+    inboxes: Vec<VecDeque<Message>>, // Messages en attente pour chaque joueur du monde
+    turns: u64,
+}
+
+impl Simulator {
+    pub fn new(config: SimulationConfig, teams: Vec<(String, Box<dyn Strategy>)>) -> Self {
+        let mut referee = Referee::new(&config);
+        let mut strategies = Vec::new();
+
+        for (name, strategy) in teams {
+            let team = referee.add_team(&name);
+            for i in 0..config.players_per_team {
+                referee.add_player(team, &format!("{}-{}", name, i));
+            }
+            strategies.push(strategy);
+        }
+
+        let inboxes = (0..referee.world.players.len()).map(|_| VecDeque::new()).collect();
+
+        Self {
+            config,
+            referee,
+            strategies,
+            inboxes,
+            turns: 0,
+        }
+    }
+
+    pub fn world(&self) -> &World {
+        &self.referee.world
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.turns >= self.config.max_turns || self.referee.all_finished()
+    }
+
+    // Joue un tour : chaque joueur encore en jeu reçoit ses messages en attente puis une vue radar.
+    // Retourne false quand la partie est terminée
+    pub fn step(&mut self) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+        self.turns += 1;
+
+        for id in 0..self.referee.world.players.len() {
+            let team = self.referee.world.players[id].team;
+            if self.referee.team_finished(team) {
+                continue;
+            }
+
+            // Seulement les messages en attente au début du tour : les réponses à ces messages
+            // attendent le tour suivant, une stratégie qui réessaie sans fin ne bloque pas la partie
+            for message in std::mem::take(&mut self.inboxes[id]) {
+                self.deliver(id, &message);
+            }
+
+            if self.referee.team_finished(team) {
+                continue;
+            }
+
+            // Indices prévus pour ce tour (secret et taille au premier tour), avant la vue radar
+            for hint in self.referee.scheduled_hints(id, self.turns - 1) {
+                self.deliver(id, &hint);
+            }
+            if let Some(radar) = self.referee.radar_view(id) {
+                self.deliver(id, &Message::RadarView(radar));
+            }
+        }
+
+        !self.is_finished()
+    }
+
+    pub fn run(mut self) -> SimulationReport {
+        while self.step() {}
+
+        let maze = &self.referee.world.maze;
+        SimulationReport {
+            turns: self.turns,
+            shortest_path: maze.distances_from(maze.start)[maze.exit.y * maze.width + maze.exit.x].unwrap_or(0),
+            teams: self.referee.reports,
+        }
+    }
+
+    fn deliver(&mut self, id: usize, message: &Message) {
+        let team = self.referee.world.players[id].team;
+        let local = self.referee.world.team_players(team).position(|other| other == id).unwrap_or(0);
+
+        for response in self.strategies[team].handle(local, message) {
+            if let Message::Action(action) = response {
+                let replies = self.referee.apply(id, action);
+                self.inboxes[id].extend(replies);
+            }
+        }
+    }
+}
+
+pub fn simulate(config: SimulationConfig, teams: Vec<(String, Box<dyn Strategy>)>) -> SimulationReport {
+    Simulator::new(config, teams).run()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{enums::Action, radar_view::WallState};
+
+    // Suit le mur de droite à partir de la vue radar, ne répond pas aux challenges
+    struct RightHand;
+
+    impl Strategy for RightHand {
+        fn handle(&mut self, _player: usize, message: &Message) -> Vec<Message> {
+            let Message::RadarView(radar) = message else {
+                return Vec::new();
+            };
+            let open = |wall: WallState| wall == WallState::Open;
+
+            let direction = if open(radar.vertical[4 + 2]) {
+                Direction::Right
+            } else if open(radar.horizontal[3 + 1]) {
+                Direction::Front
+            } else if open(radar.vertical[4 + 1]) {
+                Direction::Left
+            } else {
+                Direction::Back
+            };
+            vec![Message::Action(Action::MoveTo(direction))]
+        }
+    }
+
+    // Fonce tout droit, même dans les murs
+    struct Stubborn;
+
+    impl Strategy for Stubborn {
+        fn handle(&mut self, _player: usize, message: &Message) -> Vec<Message> {
+            match message {
+                Message::RadarView(_) => vec![Message::Action(Action::MoveTo(Direction::Front))],
+                _ => Vec::new(),
+            }
+        }
+    }
+
+    // Répond à chaque message, erreurs comprises, par une mauvaise réponse au challenge
+    struct Retry;
+
+    impl Strategy for Retry {
+        fn handle(&mut self, _player: usize, _message: &Message) -> Vec<Message> {
+            vec![Message::Action(Action::SolveChallenge { answer: String::from("wrong") })]
+        }
+    }
+
+    #[test]
+    fn test_endless_retries_still_end_the_game() {
+        let config = SimulationConfig {
+            players_per_team: 1,
+            max_turns: 20,
+            challenge_every: Some(1),
+            ..SimulationConfig::default()
+        };
+        let report = simulate(config, vec![(String::from("retry"), Box::new(Retry))]);
+        assert_eq!(report.turns, 20);
+        assert_eq!(report.teams[0].moves, 0);
+    }
+
+    #[test]
+    fn test_config_from_args() {
+        let args = |text: &str| text.split_whitespace().map(String::from).collect::<Vec<_>>();
+
+        let config = SimulationConfig::from_args(&args(
+            "server --width 6 --players 2 --monsters chase,patrol --penalty freeze:2 --secret-every 5 --no-grid-size",
+        ))
+        .unwrap();
+        assert_eq!((config.width, config.height, config.players_per_team), (6, 10, 2));
+        assert_eq!(config.monsters, [MonsterBehavior::Chase, MonsterBehavior::Patrol]);
+        assert_eq!(config.catch_penalty, CatchPenalty::Freeze { turns: 2 });
+        assert_eq!((config.secret_every, config.challenge_every), (Some(5), None));
+        assert!(!config.grid_size_hint);
+
+        for (text, error) in [
+            ("--height 0", "invalid value for --height: 0"),
+            ("--players 300", "invalid value for --players: 300, at most 255"),
+            ("--challenge-every often", "invalid value for --challenge-every: often"),
+            ("--monsters dragon", "--monsters: unknown monster behavior \"dragon\", expected random, patrol or chase"),
+        ] {
+            assert_eq!(SimulationConfig::from_args(&args(text)).unwrap_err(), error);
+        }
+    }
+
+    #[test]
+    fn test_right_hand_reaches_exit() {
+        let config = SimulationConfig {
+            width: 8,
+            height: 8,
+            players_per_team: 1,
+            seed: 3,
+            ..SimulationConfig::default()
+        };
+        let report = simulate(config, vec![(String::from("right-hand"), Box::new(RightHand))]);
+
+        let team = &report.teams[0];
+        assert!(team.moves_to_exit.is_some());
+        assert!(team.moves_to_exit.unwrap() >= report.shortest_path as u64);
+        assert_eq!(team.wall_collisions, 0);
+    }
+
+    #[test]
+    fn test_collisions_and_determinism() {
+        let config = SimulationConfig {
+            max_turns: 50,
+            ..SimulationConfig::default()
+        };
+        let teams = || -> Vec<(String, Box<dyn Strategy>)> {
+            vec![
+                (String::from("stubborn"), Box::new(Stubborn)),
+                (String::from("client"), Box::new(ClientStrategy::new(3))),
+            ]
+        };
+
+        let report = simulate(config.clone(), teams());
+        assert_eq!(report.turns, 50);
+        assert!(report.teams[0].wall_collisions > 0);
+        assert_eq!(report, simulate(config, teams()));
+    }
+
+    // Déplacements à la main droite, le reste (secrets, challenges) est géré par le client
+    struct RightHandClient(ClientStrategy);
+
+    impl Strategy for RightHandClient {
+        fn handle(&mut self, player: usize, message: &Message) -> Vec<Message> {
+            match message {
+                Message::RadarView(_) => RightHand.handle(player, message),
+                _ => self.0.handle(player, message),
+            }
+        }
+    }
+
+    #[test]
+    fn test_client_solves_challenges() {
+        let config = SimulationConfig {
+            max_turns: 60,
+            challenge_every: Some(3),
+            seed: 11,
+            ..SimulationConfig::default()
+        };
+        let report = simulate(config, vec![(String::from("client"), Box::new(RightHandClient(ClientStrategy::new(3))))]);
+
+        let team = &report.teams[0];
+        assert!(team.challenges > 0);
+        assert_eq!(team.challenge_failures, 0);
+    }
+}
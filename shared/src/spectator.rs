@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    maze::{Heading, Maze},
+    referee::GameEvent,
+    structs::Position,
+    world::{Monster, World, WorldPlayer},
+};
+
+// Messages du serveur de test vers un spectateur, avec le même cadrage que le jeu (taille u32
+// little-endian puis JSON). Le labyrinthe complet est envoyé une fois, puis à chaque nouveau
+// match, suivi d'une mise à jour du monde par tick. Le spectateur n'envoie rien
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SpectatorMessage {
+    Maze(Maze),
+    Tick(WorldUpdate),
+}
+
+// Ce que le spectateur voit d'un joueur (pas son secret)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerState {
+    pub team: usize,
+    pub name: String,
+    pub position: Position,
+    pub heading: Heading,
+    pub exited: bool,
+    pub challenge: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorldUpdate {
+    pub tick: u64,
+    pub teams: Vec<String>,
+    pub players: Vec<PlayerState>,
+    pub monsters: Vec<Monster>,
+    pub hints: Vec<Position>,
+    pub events: Vec<GameEvent>, // Évènements depuis le tick précédent
+}
+
+impl WorldUpdate {
+    pub fn new(tick: u64, world: &World, events: Vec<GameEvent>) -> Self {
+        Self {
+            tick,
+            teams: world.teams.clone(),
+            players: world
+                .players
+                .iter()
+                .map(|player| PlayerState {
+                    team: player.team,
+                    name: player.name.clone(),
+                    position: player.position,
+                    heading: player.heading,
+                    exited: player.exited,
+                    challenge: player.challenge,
+                })
+                .collect(),
+            monsters: world.monsters.clone(),
+            hints: world.hints.clone(),
+            events,
+        }
+    }
+
+    // Monde reconstruit côté spectateur, pour l'afficher avec `World::render`
+    pub fn to_world(&self, maze: &Maze) -> World {
+        let mut world = World::new(maze.clone());
+        world.teams = self.teams.clone();
+        world.monsters = self.monsters.clone();
+        world.hints = self.hints.clone();
+        world.players = self
+            .players
+            .iter()
+            .map(|player| WorldPlayer {
+                name: player.name.clone(),
+                team: player.team,
+                position: player.position,
+                heading: player.heading,
+                secret: 0,
+                challenge: player.challenge,
+                exited: player.exited,
+                frozen: 0,
+            })
+            .collect();
+        world
+    }
+
+    // Évènement en texte, avec le nom du joueur et de son équipe
+    pub fn describe(&self, event: &GameEvent) -> String {
+        let who = |player: usize| match self.players.get(player) {
+            Some(state) => format!("{} ({})", state.name, self.teams.get(state.team).map_or("?", String::as_str)),
+            None => format!("joueur {player}"),
+        };
+
+        match event {
+            GameEvent::ChallengeStarted { player, modulo } => {
+                format!("{}: challenge SecretSumModulo({modulo})", who(*player))
+            }
+            GameEvent::ChallengeSolved { player } => format!("{}: challenge résolu", who(*player)),
+            GameEvent::ChallengeFailed { player } => format!("{}: mauvaise réponse au challenge", who(*player)),
+            GameEvent::CaughtByMonster { player } => format!("{}: attrapé par un monstre", who(*player)),
+            GameEvent::ReachedExit { player } => format!("{}: sortie trouvée", who(*player)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::world::MonsterBehavior;
+
+    #[test]
+    fn test_update_rebuilds_world() {
+        let maze = Maze::generate(5, 4, &mut StdRng::seed_from_u64(3));
+        let mut world = World::new(maze.clone());
+        let team = world.add_team("team");
+        let player = world.add_player(team, "Player-0", 987_654);
+        world.add_monster(MonsterBehavior::Patrol, Position { x: 2, y: 2 });
+        world.add_hint(Position { x: 1, y: 3 });
+        world.start_challenge(player, 7);
+
+        let update = WorldUpdate::new(3, &world, vec![GameEvent::ChallengeStarted { player, modulo: 7 }]);
+        let text = serde_json::to_string(&SpectatorMessage::Tick(update)).unwrap();
+        assert!(!text.contains("987654"), "le secret ne doit pas être envoyé: {text}");
+
+        let SpectatorMessage::Tick(received) = serde_json::from_str(&text).unwrap() else {
+            panic!("{text}");
+        };
+        assert_eq!(received.tick, 3);
+        assert_eq!(received.to_world(&maze).render(), world.render());
+        assert_eq!(
+            received.describe(&received.events[0]),
+            "Player-0 (team): challenge SecretSumModulo(7)"
+        );
+        assert_eq!(received.describe(&GameEvent::ReachedExit { player: 9 }), "joueur 9: sortie trouvée");
+    }
+}
@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+
+use rand::{distr::Alphanumeric, rngs::StdRng, Rng, SeedableRng};
+
+use crate::enums::RegistrationError;
+
+const TOKEN_LENGTH: usize = 12;
+
+// Jetons d'inscription des équipes, indexés pour retrouver l'équipe d'un jeton en O(1).
+// Les jetons viennent d'un générateur cryptographiquement sûr initialisé par le système, ou
+// d'une graine pour rejouer une partie à l'identique. Le registre applique aussi les règles
+// d'inscription des serveurs de test : au plus `expected_teams` équipes par match, et plus
+// aucune une fois le match terminé, jusqu'à sa réinitialisation
+pub struct TokenRegistry<T> {
+    rng: StdRng,
+    tokens: HashMap<String, T>,
+    expected_teams: usize,
+    teams: usize, // Équipes inscrites depuis le début du match
+    closed: bool, // Match terminé
+}
+
+impl<T> TokenRegistry<T> {
+    pub fn new(seed: Option<u64>, expected_teams: usize) -> Self {
+        Self {
+            rng: seed.map_or_else(StdRng::from_os_rng, StdRng::seed_from_u64),
+            tokens: HashMap::new(),
+            expected_teams,
+            teams: 0,
+            closed: false,
+        }
+    }
+
+    // Jeton d'une nouvelle équipe. Une équipe en trop ou arrivée après la fin du match reçoit
+    // TooManyPlayers, le protocole n'ayant pas d'erreur dédiée
+    pub fn register(&mut self, value: T) -> Result<String, RegistrationError> {
+        if self.closed || self.teams >= self.expected_teams {
+            return Err(RegistrationError::TooManyPlayers);
+        }
+        let token = self.generate();
+        self.insert(token.clone(), value);
+        self.teams += 1;
+        Ok(token)
+    }
+
+    // Nouveau jeton, différent de tous les jetons valides
+    pub fn generate(&mut self) -> String {
+        loop {
+            let token: String = (&mut self.rng)
+                .sample_iter(&Alphanumeric)
+                .take(TOKEN_LENGTH)
+                .map(char::from)
+                .collect();
+            if !self.tokens.contains_key(&token) {
+                return token;
+            }
+        }
+    }
+
+    pub fn insert(&mut self, token: String, value: T) {
+        self.tokens.insert(token, value);
+    }
+
+    pub fn get(&self, token: &str) -> Option<&T> {
+        self.tokens.get(token)
+    }
+
+    // Fin du match : plus aucun jeton n'est accepté ni délivré. Faux si le match était déjà fini
+    pub fn close(&mut self) -> bool {
+        self.tokens.clear();
+        !std::mem::replace(&mut self.closed, true)
+    }
+
+    // Nouveau match : les équipes doivent se réinscrire
+    pub fn reset(&mut self) {
+        self.tokens.clear();
+        self.teams = 0;
+        self.closed = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens() {
+        let mut registry = TokenRegistry::new(None, 2);
+        let first = registry.register("blue").unwrap();
+        assert_eq!(first.len(), TOKEN_LENGTH);
+        assert!(first.chars().all(|c| c.is_ascii_alphanumeric()));
+
+        let second = registry.generate();
+        assert_ne!(first, second);
+        assert_eq!(registry.get(&first), Some(&"blue"));
+        assert_eq!(registry.get(&second), None);
+
+        // Deux équipes au plus
+        registry.register("red").unwrap();
+        assert!(matches!(registry.register("green"), Err(RegistrationError::TooManyPlayers)));
+
+        // Fin du match : jetons invalidés, inscriptions fermées jusqu'à la réinitialisation
+        assert!(registry.close());
+        assert!(!registry.close());
+        assert_eq!(registry.get(&first), None);
+        registry.reset();
+        assert!(registry.register("green").is_ok());
+        assert!(registry.close());
+        assert!(matches!(registry.register("blue"), Err(RegistrationError::TooManyPlayers)));
+
+        // Avec une graine, les mêmes jetons à chaque partie
+        let seeded = || TokenRegistry::<()>::new(Some(3), 1).generate();
+        assert_eq!(seeded(), seeded());
+    }
+}
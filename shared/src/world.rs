@@ -0,0 +1,572 @@
+use std::str::FromStr;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    enums::{ActionError, Challenge},
+    game_engine::Direction,
+    maze::{Heading, Maze, Occupants},
+    radar_view::{Element, Entity, RadarView},
+    structs::Position,
+};
+
+const HEADINGS: [Heading; 4] = [Heading::North, Heading::East, Heading::South, Heading::West];
+
+#[derive(Debug, Clone)]
+pub struct WorldPlayer {
+    pub name: String,
+    pub team: usize,
+    pub position: Position,
+    pub heading: Heading,
+    pub secret: u64,
+    pub challenge: Option<u64>, // Modulo du challenge en cours
+    pub exited: bool,           // Le joueur a trouvé la sortie et a quitté le labyrinthe
+    pub frozen: u64,            // Déplacements encore ignorés après avoir été attrapé
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveOutcome {
+    Moved,
+    ReachedExit,
+    Caught, // Le joueur est entré sur la case d'un monstre
+    Frozen, // Déplacement ignoré, le joueur purge sa pénalité
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MonsterBehavior {
+    RandomWalk, // Une case ouverte au hasard à chaque tour
+    Patrol,     // Tout droit, tourne à droite devant un mur
+    Chase,      // Se rapproche du joueur le plus proche dans le labyrinthe
+}
+
+impl FromStr for MonsterBehavior {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "random" => Ok(MonsterBehavior::RandomWalk),
+            "patrol" => Ok(MonsterBehavior::Patrol),
+            "chase" => Ok(MonsterBehavior::Chase),
+            other => Err(format!("unknown monster behavior {other:?}, expected random, patrol or chase")),
+        }
+    }
+}
+
+// Pénalité d'un joueur attrapé par un monstre
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CatchPenalty {
+    #[default]
+    Respawn,               // Retour à la case de départ
+    Freeze { turns: u64 }, // Les prochains déplacements sont ignorés
+}
+
+impl FromStr for CatchPenalty {
+    type Err = String;
+
+    // `respawn` ou `freeze:<tours>`
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.split_once(':') {
+            None if text == "respawn" => Ok(CatchPenalty::Respawn),
+            Some(("freeze", turns)) => turns
+                .parse()
+                .map(|turns| CatchPenalty::Freeze { turns })
+                .map_err(|_| format!("invalid freeze duration {turns:?}")),
+            _ => Err(format!("unknown penalty {text:?}, expected respawn or freeze:<turns>")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Monster {
+    pub behavior: MonsterBehavior,
+    pub position: Position,
+    pub heading: Heading,
+}
+
+// Règles du jeu, indépendantes du réseau : positions, déplacements, vues radar et challenges
+#[derive(Debug, Clone)]
+pub struct World {
+    pub maze: Maze,
+    pub teams: Vec<String>,
+    pub players: Vec<WorldPlayer>,
+    pub monsters: Vec<Monster>,
+    pub catch_penalty: CatchPenalty,
+    pub hints: Vec<Position>, // Cases indice : y entrer donne une boussole vers la sortie
+}
+
+impl World {
+    pub fn new(maze: Maze) -> Self {
+        Self {
+            maze,
+            teams: Vec::new(),
+            players: Vec::new(),
+            monsters: Vec::new(),
+            catch_penalty: CatchPenalty::default(),
+            hints: Vec::new(),
+        }
+    }
+
+    pub fn add_team(&mut self, name: &str) -> usize {
+        self.teams.push(name.to_string());
+        self.teams.len() - 1
+    }
+
+    // Place un nouveau joueur sur la case de départ, orienté vers le nord
+    pub fn add_player(&mut self, team: usize, name: &str, secret: u64) -> usize {
+        self.players.push(WorldPlayer {
+            name: name.to_string(),
+            team,
+            position: self.maze.start,
+            heading: Heading::North,
+            secret,
+            challenge: None,
+            exited: false,
+            frozen: 0,
+        });
+        self.players.len() - 1
+    }
+
+    pub fn add_monster(&mut self, behavior: MonsterBehavior, position: Position) -> usize {
+        self.monsters.push(Monster {
+            behavior,
+            position,
+            heading: Heading::North,
+        });
+        self.monsters.len() - 1
+    }
+
+    pub fn add_hint(&mut self, position: Position) {
+        if !self.hints.contains(&position) {
+            self.hints.push(position);
+        }
+    }
+
+    pub fn is_hint(&self, position: Position) -> bool {
+        self.hints.contains(&position)
+    }
+
+    // Direction de la sortie vue par le joueur : angle en degrés dans le sens horaire depuis
+    // son orientation, entre 0 et 360
+    pub fn compass_angle(&self, player: usize) -> f32 {
+        let player = &self.players[player];
+        let dx = self.maze.exit.x as f32 - player.position.x as f32;
+        let dy = self.maze.exit.y as f32 - player.position.y as f32;
+
+        // y croît vers le sud : le nord est (0, -1)
+        let bearing = dx.atan2(-dy).to_degrees();
+        (bearing - player.heading.degrees()).rem_euclid(360.0)
+    }
+
+    fn monster_at(&self, position: Position) -> bool {
+        self.monsters.iter().any(|monster| monster.position == position)
+    }
+
+    // Applique la pénalité à un joueur attrapé par un monstre
+    fn catch(&mut self, player: usize) {
+        let start = self.maze.start;
+        let caught = &mut self.players[player];
+        match self.catch_penalty {
+            CatchPenalty::Respawn => {
+                caught.position = start;
+                caught.heading = Heading::North;
+            }
+            CatchPenalty::Freeze { turns } => caught.frozen = turns,
+        }
+    }
+
+    // Déplace chaque monstre d'une case selon son comportement. Les monstres évitent la case de
+    // départ pour qu'un joueur renvoyé au départ ne soit pas attrapé à nouveau, et un joueur gelé
+    // ne peut pas être attrapé avant d'avoir purgé sa pénalité. Retourne les joueurs attrapés
+    pub fn move_monsters<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Vec<usize> {
+        for index in 0..self.monsters.len() {
+            let monster = &self.monsters[index];
+            let open: Vec<Heading> = HEADINGS
+                .into_iter()
+                .filter(|&heading| {
+                    self.maze
+                        .step(monster.position, heading)
+                        .is_some_and(|target| target != self.maze.start)
+                })
+                .collect();
+            if open.is_empty() {
+                continue;
+            }
+
+            let heading = match monster.behavior {
+                MonsterBehavior::RandomWalk => open[rng.random_range(0..open.len())],
+                MonsterBehavior::Patrol => [Direction::Front, Direction::Right, Direction::Left, Direction::Back]
+                    .into_iter()
+                    .map(|direction| monster.heading.turn(direction))
+                    .find(|heading| open.contains(heading))
+                    .unwrap_or(open[0]),
+                MonsterBehavior::Chase => self.chase_heading(monster.position, &open).unwrap_or(open[0]),
+            };
+
+            let monster = &mut self.monsters[index];
+            monster.heading = heading;
+            monster.position = self.maze.step(monster.position, heading).unwrap_or(monster.position);
+        }
+
+        let caught: Vec<usize> = (0..self.players.len())
+            .filter(|&id| {
+                let player = &self.players[id];
+                !player.exited && player.frozen == 0 && self.monster_at(player.position)
+            })
+            .collect();
+        for &id in &caught {
+            self.catch(id);
+        }
+        caught
+    }
+
+    // Premier pas vers le joueur le plus proche (en nombre de cases à parcourir), hors joueurs gelés
+    fn chase_heading(&self, from: Position, open: &[Heading]) -> Option<Heading> {
+        let index = |position: Position| position.y * self.maze.width + position.x;
+        let from_monster = self.maze.distances_from(from);
+        let target = self
+            .players
+            .iter()
+            .filter(|player| !player.exited && player.frozen == 0)
+            .filter_map(|player| from_monster[index(player.position)].map(|distance| (distance, player.position)))
+            .min_by_key(|(distance, _)| *distance)?
+            .1;
+
+        let to_target = self.maze.distances_from(target);
+        open.iter()
+            .copied()
+            .filter_map(|heading| {
+                let next = self.maze.step(from, heading)?;
+                to_target[index(next)].map(|distance| (distance, heading))
+            })
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, heading)| heading)
+    }
+
+    // Labyrinthe en texte avec tout ce qu'il contient : chiffre de l'équipe pour un joueur,
+    // M monstre, E sortie, H indice, S départ
+    pub fn render(&self) -> String {
+        let maze = &self.maze;
+        let wall = |x: usize, y: usize, heading: Heading| maze.has_wall(Position { x, y }, heading);
+        let symbol = |position: Position| {
+            if let Some(player) = self.players.iter().find(|player| !player.exited && player.position == position) {
+                return char::from_digit(player.team as u32, 10).unwrap_or('*');
+            }
+            if self.monster_at(position) {
+                'M'
+            } else if position == maze.exit {
+                'E'
+            } else if self.is_hint(position) {
+                'H'
+            } else if position == maze.start {
+                'S'
+            } else {
+                ' '
+            }
+        };
+
+        let mut text = String::new();
+        for y in 0..maze.height {
+            for x in 0..maze.width {
+                text.push_str(if wall(x, y, Heading::North) { "+---" } else { "+   " });
+            }
+            text.push_str("+\n");
+            for x in 0..maze.width {
+                text.push(if wall(x, y, Heading::West) { '|' } else { ' ' });
+                text.push_str(&format!(" {} ", symbol(Position { x, y })));
+            }
+            text.push(if wall(maze.width - 1, y, Heading::East) { '|' } else { ' ' });
+            text.push('\n');
+        }
+        for x in 0..maze.width {
+            text.push_str(if wall(x, maze.height - 1, Heading::South) { "+---" } else { "+   " });
+        }
+        text.push_str("+\n");
+        text
+    }
+
+    pub fn team_players(&self, team: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..self.players.len()).filter(move |&id| self.players[id].team == team)
+    }
+
+    pub fn radar_view(&self, player: usize) -> RadarView {
+        let viewer = &self.players[player];
+        self.maze.radar_view(viewer.position, viewer.heading, &Viewer { world: self, player })
+    }
+
+    // Joueur encore dans le labyrinthe sur cette case, autre que `except`
+    fn player_at(&self, position: Position, except: usize) -> Option<&WorldPlayer> {
+        self.players
+            .iter()
+            .enumerate()
+            .find(|(id, other)| *id != except && !other.exited && other.position == position)
+            .map(|(_, other)| other)
+    }
+
+    pub fn move_player(&mut self, player: usize, direction: Direction) -> Result<MoveOutcome, ActionError> {
+        if self.players[player].frozen > 0 {
+            self.players[player].frozen -= 1;
+            return Ok(MoveOutcome::Frozen);
+        }
+
+        let current = &self.players[player];
+        if current.challenge.is_some() {
+            return Err(ActionError::SolveChallengeFirst);
+        }
+
+        let heading = current.heading.turn(direction);
+        let Some(target) = self.maze.step(current.position, heading) else {
+            return Err(ActionError::CannotPassThroughWall);
+        };
+
+        if self
+            .player_at(target, player)
+            .is_some_and(|other| other.team != current.team)
+        {
+            return Err(ActionError::CannotPassThroughOpponent);
+        }
+
+        let current = &mut self.players[player];
+        current.position = target;
+        current.heading = heading;
+
+        if target == self.maze.exit {
+            current.exited = true;
+            return Ok(MoveOutcome::ReachedExit);
+        }
+        if self.monster_at(target) {
+            self.catch(player);
+            return Ok(MoveOutcome::Caught);
+        }
+        Ok(MoveOutcome::Moved)
+    }
+
+    // Somme des secrets de l'équipe modulo `modulo`
+    pub fn expected_answer(&self, team: usize, modulo: u64) -> u64 {
+        let total: u128 = self
+            .team_players(team)
+            .map(|id| self.players[id].secret as u128)
+            .sum();
+        (total % modulo.max(1) as u128) as u64
+    }
+
+    pub fn start_challenge(&mut self, player: usize, modulo: u64) -> Challenge {
+        self.players[player].challenge = Some(modulo);
+        Challenge::SecretSumModulo(modulo)
+    }
+
+    pub fn solve_challenge(&mut self, player: usize, answer: &str) -> Result<(), ActionError> {
+        let Some(modulo) = self.players[player].challenge else {
+            return Err(ActionError::NoRunningChallenge);
+        };
+
+        let expected = self.expected_answer(self.players[player].team, modulo);
+        if answer.trim().parse::<u64>() != Ok(expected) {
+            return Err(ActionError::InvalidChallengeSolution);
+        }
+
+        self.players[player].challenge = None;
+        Ok(())
+    }
+}
+
+// Ce que voit un joueur : ses coéquipiers sont des alliés, les autres des ennemis
+struct Viewer<'a> {
+    world: &'a World,
+    player: usize,
+}
+
+impl Occupants for Viewer<'_> {
+    fn entity_at(&self, x: i64, y: i64) -> Entity {
+        let position = Position { x: x as usize, y: y as usize };
+        if self.world.monster_at(position) {
+            return Entity::Monster;
+        }
+        match self.world.player_at(position, self.player) {
+            Some(other) if other.team == self.world.players[self.player].team => Entity::Ally,
+            Some(_) => Entity::Enemy,
+            None => Entity::None,
+        }
+    }
+
+    fn element_at(&self, x: i64, y: i64) -> Element {
+        if self.world.is_hint(Position { x: x as usize, y: y as usize }) {
+            return Element::Hint;
+        }
+        Element::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::radar_view::Cell;
+
+    fn corridor_world() -> World {
+        // Couloir de 4 cases d'ouest en est, départ à l'ouest
+        let mut maze = Maze::generate(4, 1, &mut StdRng::seed_from_u64(1));
+        maze.start = Position { x: 0, y: 0 };
+        maze.exit = Position { x: 3, y: 0 };
+        World::new(maze)
+    }
+
+    #[test]
+    fn test_moves_walls_and_exit() {
+        let mut world = corridor_world();
+        let team = world.add_team("team");
+        let player = world.add_player(team, "Player-0", 1);
+
+        assert_eq!(world.move_player(player, Direction::Front), Err(ActionError::CannotPassThroughWall));
+        assert_eq!(world.move_player(player, Direction::Right), Ok(MoveOutcome::Moved));
+        assert_eq!(world.players[player].heading, Heading::East);
+        assert_eq!(world.move_player(player, Direction::Front), Ok(MoveOutcome::Moved));
+        assert_eq!(world.move_player(player, Direction::Front), Ok(MoveOutcome::ReachedExit));
+        assert!(world.players[player].exited);
+    }
+
+    #[test]
+    fn test_opponents_block_and_are_seen_as_enemies() {
+        let mut world = corridor_world();
+        let blue = world.add_team("blue");
+        let red = world.add_team("red");
+        let runner = world.add_player(blue, "blue-0", 1);
+        let ally = world.add_player(blue, "blue-1", 1);
+        let blocker = world.add_player(red, "red-0", 1);
+        world.players[blocker].position = Position { x: 1, y: 0 };
+        world.players[ally].position = Position { x: 2, y: 0 };
+
+        assert_eq!(world.move_player(runner, Direction::Right), Err(ActionError::CannotPassThroughOpponent));
+
+        world.players[runner].heading = Heading::East;
+        let radar = world.radar_view(runner);
+        assert_eq!(radar.cells[1], Cell::Valid { element: Element::None, entity: Entity::Enemy });
+        assert_eq!(radar.cells[4], Cell::Valid { element: Element::None, entity: Entity::None });
+
+        let radar = world.radar_view(blocker);
+        assert_eq!(radar.cells[3], Cell::Valid { element: Element::None, entity: Entity::Enemy });
+        assert_eq!(radar.cells[5], Cell::Valid { element: Element::None, entity: Entity::Enemy });
+    }
+
+    #[test]
+    fn test_challenge() {
+        let mut world = corridor_world();
+        let team = world.add_team("team");
+        let first = world.add_player(team, "Player-0", 20);
+        world.add_player(team, "Player-1", 22);
+
+        assert_eq!(world.solve_challenge(first, "2"), Err(ActionError::NoRunningChallenge));
+        world.start_challenge(first, 10);
+        assert_eq!(world.move_player(first, Direction::Right), Err(ActionError::SolveChallengeFirst));
+        assert_eq!(world.solve_challenge(first, "3"), Err(ActionError::InvalidChallengeSolution));
+        assert_eq!(world.solve_challenge(first, "2"), Ok(()));
+        assert_eq!(world.move_player(first, Direction::Right), Ok(MoveOutcome::Moved));
+    }
+
+    #[test]
+    fn test_monsters_catch_players() {
+        let mut world = corridor_world();
+        let team = world.add_team("team");
+        let player = world.add_player(team, "Player-0", 1);
+        world.add_monster(MonsterBehavior::Chase, Position { x: 2, y: 0 });
+
+        // Le monstre est visible devant le joueur
+        assert_eq!(world.move_player(player, Direction::Right), Ok(MoveOutcome::Moved));
+        let radar = world.radar_view(player);
+        assert_eq!(radar.cells[1], Cell::Valid { element: Element::None, entity: Entity::Monster });
+
+        // Il rattrape le joueur, renvoyé au départ
+        assert_eq!(world.move_monsters(&mut StdRng::seed_from_u64(0)), vec![player]);
+        assert_eq!(world.players[player].position, world.maze.start);
+        assert_eq!(world.players[player].heading, Heading::North);
+
+        // Entrer sur la case d'un monstre gèle les prochains déplacements
+        world.catch_penalty = CatchPenalty::Freeze { turns: 2 };
+        assert_eq!(world.move_player(player, Direction::Right), Ok(MoveOutcome::Caught));
+        assert_eq!(world.move_player(player, Direction::Front), Ok(MoveOutcome::Frozen));
+        assert_eq!(world.move_player(player, Direction::Front), Ok(MoveOutcome::Frozen));
+        assert_eq!(world.move_player(player, Direction::Front), Ok(MoveOutcome::Moved));
+    }
+
+    #[test]
+    fn test_frozen_players_are_not_caught_again() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut world = corridor_world();
+        world.catch_penalty = CatchPenalty::Freeze { turns: 2 };
+        let team = world.add_team("team");
+        let player = world.add_player(team, "Player-0", 1);
+        assert_eq!(world.move_player(player, Direction::Right), Ok(MoveOutcome::Moved));
+        assert_eq!(world.move_player(player, Direction::Front), Ok(MoveOutcome::Moved));
+        world.add_monster(MonsterBehavior::Chase, Position { x: 3, y: 0 });
+        assert_eq!(world.move_monsters(&mut rng), vec![player]);
+
+        // Le monstre quitte la case puis y revient sans attraper à nouveau le joueur gelé
+        for _ in 0..2 {
+            assert!(world.move_monsters(&mut rng).is_empty());
+        }
+        assert_eq!(world.monsters[0].position, world.players[player].position);
+        assert_eq!(world.move_player(player, Direction::Front), Ok(MoveOutcome::Frozen));
+        assert_eq!(world.move_player(player, Direction::Front), Ok(MoveOutcome::Frozen));
+        assert_eq!(world.move_player(player, Direction::Front), Ok(MoveOutcome::ReachedExit));
+    }
+
+    #[test]
+    fn test_monster_behaviors() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut world = corridor_world();
+        let patrol = world.add_monster(MonsterBehavior::Patrol, Position { x: 3, y: 0 });
+        let walker = world.add_monster(MonsterBehavior::RandomWalk, Position { x: 1, y: 0 });
+
+        // La patrouille longe le couloir et fait demi-tour avant la case de départ
+        let mut path = Vec::new();
+        for _ in 0..4 {
+            world.move_monsters(&mut rng);
+            path.push(world.monsters[patrol].position.x);
+            // Le monstre aléatoire n'entre jamais sur la case de départ
+            assert_ne!(world.monsters[walker].position, world.maze.start);
+        }
+        assert_eq!(path, [2, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_hints_and_compass() {
+        let mut world = corridor_world();
+        let team = world.add_team("team");
+        let player = world.add_player(team, "Player-0", 1);
+        world.add_hint(Position { x: 1, y: 0 });
+
+        // Sortie plein est : à droite d'un joueur tourné vers le nord, devant lui une fois tourné
+        assert_eq!(world.compass_angle(player), 90.0);
+        world.players[player].heading = Heading::East;
+        assert_eq!(world.compass_angle(player), 0.0);
+        world.players[player].heading = Heading::South;
+        assert_eq!(world.compass_angle(player), 270.0);
+
+        world.players[player].heading = Heading::East;
+        let radar = world.radar_view(player);
+        assert_eq!(radar.cells[1], Cell::Valid { element: Element::Hint, entity: Entity::None });
+        assert!(world.is_hint(Position { x: 1, y: 0 }));
+        assert!(!world.is_hint(Position { x: 2, y: 0 }));
+    }
+
+    #[test]
+    fn test_render() {
+        let mut world = corridor_world();
+        let team = world.add_team("team");
+        let player = world.add_player(team, "Player-0", 1);
+        world.players[player].position = Position { x: 1, y: 0 };
+        world.add_monster(MonsterBehavior::Patrol, Position { x: 2, y: 0 });
+
+        assert_eq!(world.render(), "+---+---+---+---+\n| S   0   M   E |\n+---+---+---+---+\n");
+    }
+
+    #[test]
+    fn test_parse_monsters_and_penalties() {
+        assert_eq!("chase".parse(), Ok(MonsterBehavior::Chase));
+        assert!("dragon".parse::<MonsterBehavior>().is_err());
+        assert_eq!("respawn".parse(), Ok(CatchPenalty::Respawn));
+        assert_eq!("freeze:3".parse(), Ok(CatchPenalty::Freeze { turns: 3 }));
+        assert!("freeze".parse::<CatchPenalty>().is_err());
+    }
+}
@@ -0,0 +1,169 @@
+// Transport asynchrone : `cargo test -p shared --features async`
+#![cfg(feature = "async")]
+
+use std::sync::{Arc, Mutex};
+
+use shared::{
+    async_functions::{connect, get_message, play, register_player, register_team, send},
+    async_server::TestServer,
+    enums::{Action, Message, RegisterTeamResult, RegistrationError, SubscribePlayerResult},
+    functions::parse_incoming,
+    game_engine::{Direction, GameState, GlobalMap, Player},
+    simulator::SimulationConfig,
+    structs::RegisterTeam,
+};
+use tokio::task::JoinSet;
+
+async fn start_server(config: SimulationConfig, teams: usize) -> (Arc<TestServer>, String) {
+    let server = Arc::new(TestServer::bind("127.0.0.1:0", config, teams).await.unwrap());
+    let address = server.local_addr().unwrap().to_string();
+
+    let running = Arc::clone(&server);
+    tokio::spawn(async move { running.run().await });
+    (server, address)
+}
+
+async fn team_token(name: &str, address: &str) -> String {
+    match register_team(name, address).await.unwrap() {
+        RegisterTeamResult::Ok { registration_token, .. } => registration_token,
+        RegisterTeamResult::Err(error) => panic!("{name}: {error:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_registration_errors() {
+    let (_server, address) = start_server(SimulationConfig::default(), 1).await;
+    let token = team_token("team", &address).await;
+
+    assert!(matches!(
+        register_team("team", &address).await.unwrap(),
+        RegisterTeamResult::Err(RegistrationError::AlreadyRegistered)
+    ));
+    assert!(matches!(
+        register_team("ab", &address).await.unwrap(),
+        RegisterTeamResult::Err(RegistrationError::InvalidName)
+    ));
+
+    // Les connexions restent ouvertes : les joueurs inscrits sont en partie
+    let mut streams = Vec::new();
+    for name in ["Player-0", "Player-1", "Player-2"] {
+        let mut stream = connect(&address).await.unwrap();
+        assert!(matches!(register_player(name, &token, &mut stream).await.unwrap(), SubscribePlayerResult::Ok));
+        streams.push(stream);
+    }
+
+    let mut stream = connect(&address).await.unwrap();
+    assert!(matches!(
+        register_player("Player-3", &token, &mut stream).await.unwrap(),
+        SubscribePlayerResult::Err(RegistrationError::TooManyPlayers)
+    ));
+    let mut stream = connect(&address).await.unwrap();
+    assert!(matches!(
+        register_player("Player-0", "unknown", &mut stream).await.unwrap(),
+        SubscribePlayerResult::Err(RegistrationError::InvalidRegistrationToken)
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_registrations_close_at_the_end_of_the_match() {
+    let config = SimulationConfig { players_per_team: 1, max_turns: 2, ..SimulationConfig::default() };
+    let (_server, address) = start_server(config, 1).await;
+    let token = team_token("team", &address).await;
+    assert!(matches!(
+        register_team("other", &address).await.unwrap(),
+        RegisterTeamResult::Err(RegistrationError::TooManyPlayers)
+    ));
+
+    let mut stream = connect(&address).await.unwrap();
+    assert!(matches!(register_player("Player-0", &token, &mut stream).await.unwrap(), SubscribePlayerResult::Ok));
+    let mut player = Player {
+        name: String::from("Player-0"),
+        position: (0, 0),
+        secret: None,
+        direction: Direction::Front,
+    };
+    let (game_state, map) = (Arc::new(Mutex::new(GameState::new())), Arc::new(Mutex::new(GlobalMap::new())));
+    while play(&mut player, &mut stream, &game_state, &map).await.is_ok() {}
+
+    // Le seul joueur attendu a fini : son jeton n'est plus valide
+    let mut stream = connect(&address).await.unwrap();
+    assert!(matches!(
+        register_player("Player-1", &token, &mut stream).await.unwrap(),
+        SubscribePlayerResult::Err(RegistrationError::InvalidRegistrationToken)
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_unexpected_message_does_not_use_a_turn() {
+    let config = SimulationConfig { players_per_team: 1, max_turns: 3, seed: 2, ..SimulationConfig::default() };
+    let (_server, address) = start_server(config, 1).await;
+    let token = team_token("lonely", &address).await;
+    let mut stream = connect(&address).await.unwrap();
+    assert!(matches!(register_player("Player-0", &token, &mut stream).await.unwrap(), SubscribePlayerResult::Ok));
+
+    // Une inscription au milieu de la partie n'est pas une action : la vue radar est renvoyée
+    let mut radars = 0;
+    while let Ok(text) = get_message(&mut stream).await {
+        if !matches!(parse_incoming(&text).unwrap(), Some(Message::RadarView(_))) {
+            continue;
+        }
+        radars += 1;
+        let reply = if radars == 1 {
+            Message::RegisterTeam(RegisterTeam { name: String::from("late") })
+        } else {
+            Message::Action(Action::MoveTo(Direction::Front))
+        };
+        send(&mut stream, &reply).await.unwrap();
+    }
+    assert_eq!(radars, 4);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_hundreds_of_players_in_one_process() {
+    let config = SimulationConfig {
+        width: 12,
+        height: 12,
+        max_turns: 20,
+        challenge_every: Some(4),
+        seed: 1,
+        ..SimulationConfig::default()
+    };
+    let (server, address) = start_server(config, 40).await;
+
+    let mut players = JoinSet::new();
+    for team in 0..40 {
+        let token = team_token(&format!("team-{team}"), &address).await;
+        let game_state = Arc::new(Mutex::new(GameState::new()));
+        let map = Arc::new(Mutex::new(GlobalMap::new()));
+
+        for i in 0..3 {
+            let (address, token, game_state, map) = (address.clone(), token.clone(), game_state.clone(), map.clone());
+            players.spawn(async move {
+                let name = format!("Player-{i}");
+                let mut stream = connect(&address).await.unwrap();
+                assert!(matches!(register_player(&name, &token, &mut stream).await.unwrap(), SubscribePlayerResult::Ok));
+
+                let mut player = Player {
+                    name,
+                    position: (0, 0),
+                    secret: None,
+                    direction: Direction::Front,
+                };
+                // Le serveur ferme la connexion après `max_turns` tours ou à la sortie
+                let mut turns = 0;
+                while play(&mut player, &mut stream, &game_state, &map).await.is_ok() {
+                    turns += 1;
+                }
+                turns
+            });
+        }
+    }
+
+    let turns = players.join_all().await;
+    assert_eq!(turns.len(), 120);
+    assert!(turns.iter().all(|&turns| turns > 0));
+
+    let world = server.world();
+    assert_eq!(world.teams.len(), 40);
+    assert_eq!(world.players.len(), 120);
+}
@@ -0,0 +1,287 @@
+// Conformité du client (`shared::functions`) face à un serveur scripté : chaque connexion
+// rejoue une séquence exacte de messages du protocole et vérifie les réponses du client
+
+use std::{
+    io::{self, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread::{self, JoinHandle},
+};
+
+use serde_json::{json, Value};
+use shared::{
+    enums::{ActionError, Challenge, Hint, Message, RegisterTeamResult, RegistrationError, SubscribePlayerResult},
+    functions::{connect, play, register_player, register_team},
+    game_engine::{Direction, GameState, GlobalMap, Player},
+    radar_view::{Cell, Element, Entity, RadarView, WallState},
+};
+
+enum Step {
+    Push(Message), // Message envoyé au client
+    PushRaw(&'static str), // JSON envoyé tel quel, par exemple une variante inconnue du client
+    Expect(Value), // Message JSON attendu du client, à l'identique
+}
+
+use Step::{Expect, Push, PushRaw};
+
+// Serveur local sur un port éphémère, un script par connexion (dans l'ordre d'arrivée)
+struct ScriptedServer {
+    address: String,
+    handle: JoinHandle<Vec<Result<(), String>>>,
+}
+
+impl ScriptedServer {
+    fn start(scripts: Vec<Vec<Step>>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+
+        let handle = thread::spawn(move || {
+            // Les connexions jouent en parallèle : un coéquipier peut attendre pendant qu'un autre joue
+            let connections: Vec<_> = scripts
+                .into_iter()
+                .map(|script| {
+                    let (stream, _) = listener.accept().unwrap();
+                    thread::spawn(move || run_script(stream, script))
+                })
+                .collect();
+            connections.into_iter().map(|connection| connection.join().unwrap()).collect()
+        });
+
+        Self { address, handle }
+    }
+
+    // Attend la fin de tous les scripts, la connexion est fermée à la fin de chacun
+    fn finish(self) {
+        for (index, result) in self.handle.join().unwrap().into_iter().enumerate() {
+            if let Err(error) = result {
+                panic!("connection {index}: {error}");
+            }
+        }
+    }
+}
+
+fn run_script(mut stream: TcpStream, script: Vec<Step>) -> Result<(), String> {
+    for (index, step) in script.into_iter().enumerate() {
+        let text = match step {
+            Push(message) => serde_json::to_string(&message).unwrap(),
+            PushRaw(text) => text.to_string(),
+            Expect(expected) => {
+                let mut size = [0u8; 4];
+                stream.read_exact(&mut size).map_err(|err| format!("step {index}: {err}"))?;
+                let mut text = vec![0u8; u32::from_le_bytes(size) as usize];
+                stream.read_exact(&mut text).map_err(|err| format!("step {index}: {err}"))?;
+
+                let received: Value = serde_json::from_slice(&text).map_err(|err| format!("step {index}: {err}"))?;
+                if received != expected {
+                    return Err(format!("step {index}: expected {expected}, received {received}"));
+                }
+                continue;
+            }
+        };
+
+        stream.write_all(&(text.len() as u32).to_le_bytes()).map_err(|err| err.to_string())?;
+        stream.write_all(text.as_bytes()).map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+fn subscribe(name: &str) -> Vec<Step> {
+    vec![
+        Expect(json!({"SubscribePlayer": {"name": name, "registration_token": "token"}})),
+        Push(Message::SubscribePlayerResult(SubscribePlayerResult::Ok)),
+    ]
+}
+
+fn player(name: &str) -> Player {
+    Player {
+        name: name.to_string(),
+        position: (0, 0),
+        secret: None,
+        direction: Direction::Front,
+    }
+}
+
+// Vue sans murs, toutes les cases libres, avec l'objectif sur la case `target`
+fn radar_with_target(target: usize) -> Message {
+    let mut cells = vec![Cell::Valid { element: Element::None, entity: Entity::None }; 9];
+    cells[target] = Cell::Valid { element: Element::Target, entity: Entity::None };
+    Message::RadarView(RadarView {
+        horizontal: vec![WallState::Open; 12],
+        vertical: vec![WallState::Open; 12],
+        cells,
+    })
+}
+
+fn shared_state() -> (Arc<Mutex<GameState>>, Arc<Mutex<GlobalMap>>) {
+    (Arc::new(Mutex::new(GameState::new())), Arc::new(Mutex::new(GlobalMap::new())))
+}
+
+#[test]
+fn test_register_team() {
+    let server = ScriptedServer::start(vec![
+        vec![
+            Expect(json!({"RegisterTeam": {"name": "team"}})),
+            Push(Message::RegisterTeamResult(RegisterTeamResult::Ok {
+                expected_players: 3,
+                registration_token: String::from("token"),
+            })),
+        ],
+        vec![
+            Expect(json!({"RegisterTeam": {"name": "team"}})),
+            Push(Message::RegisterTeamResult(RegisterTeamResult::Err(RegistrationError::AlreadyRegistered))),
+        ],
+        vec![
+            Expect(json!({"RegisterTeam": {"name": ""}})),
+            Push(Message::RegisterTeamResult(RegisterTeamResult::Err(RegistrationError::InvalidName))),
+        ],
+    ]);
+
+    assert!(matches!(
+        register_team("team", &server.address).unwrap(),
+        RegisterTeamResult::Ok { expected_players: 3, registration_token } if registration_token == "token"
+    ));
+    assert!(matches!(
+        register_team("team", &server.address).unwrap(),
+        RegisterTeamResult::Err(RegistrationError::AlreadyRegistered)
+    ));
+    assert!(matches!(
+        register_team("", &server.address).unwrap(),
+        RegisterTeamResult::Err(RegistrationError::InvalidName)
+    ));
+    server.finish();
+}
+
+#[test]
+fn test_register_player_errors() {
+    let token = String::from("token");
+    let server = ScriptedServer::start(vec![
+        subscribe("Player-0"),
+        vec![
+            Expect(json!({"SubscribePlayer": {"name": "Player-1", "registration_token": "token"}})),
+            Push(Message::SubscribePlayerResult(SubscribePlayerResult::Err(
+                RegistrationError::TooManyPlayers,
+            ))),
+        ],
+        vec![
+            Expect(json!({"SubscribePlayer": {"name": "Player-2", "registration_token": "token"}})),
+            Push(Message::SubscribePlayerResult(SubscribePlayerResult::Err(
+                RegistrationError::InvalidRegistrationToken,
+            ))),
+        ],
+        // Le serveur ferme la connexion sans répondre
+        vec![Expect(json!({"SubscribePlayer": {"name": "Player-3", "registration_token": "token"}}))],
+    ]);
+
+    assert!(register_player("Player-0", &token, &mut connect(&server.address)).unwrap());
+    assert!(!register_player("Player-1", &token, &mut connect(&server.address)).unwrap());
+    assert!(!register_player("Player-2", &token, &mut connect(&server.address)).unwrap());
+    assert!(register_player("Player-3", &token, &mut connect(&server.address)).is_err());
+    server.finish();
+}
+
+#[test]
+fn test_play_walls() {
+    let mut script = subscribe("Player-0");
+    script.extend([
+        Push(radar_with_target(5)),
+        Expect(json!({"Action": {"MoveTo": "Right"}})),
+        // Le déplacement est refusé : le client ne répond pas et attend la vue suivante
+        Push(Message::ActionError(ActionError::CannotPassThroughWall)),
+        Push(radar_with_target(1)),
+        Expect(json!({"Action": {"MoveTo": "Front"}})),
+    ]);
+    let server = ScriptedServer::start(vec![script]);
+
+    let (mut game_state, mut map) = shared_state();
+    let mut stream = connect(&server.address);
+    let mut player = player("Player-0");
+    assert!(register_player("Player-0", &String::from("token"), &mut stream).unwrap());
+
+    assert_eq!(play(&mut player, &mut stream, &mut game_state, &mut map).unwrap().sent.len(), 1);
+    assert!(play(&mut player, &mut stream, &mut game_state, &mut map).unwrap().sent.is_empty());
+    assert_eq!(play(&mut player, &mut stream, &mut game_state, &mut map).unwrap().sent.len(), 1);
+
+    // Fin du script : la connexion fermée est une fin de partie, pas une panique
+    let error = play(&mut player, &mut stream, &mut game_state, &mut map).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+    server.finish();
+
+    let stats = &game_state.lock().unwrap().stats["Player-0"];
+    assert_eq!(stats.moves, 2);
+    assert_eq!(stats.wall_collisions, 1);
+}
+
+#[test]
+fn test_play_skips_unknown_messages() {
+    let mut script = subscribe("Player-0");
+    script.extend([
+        PushRaw(r#"{"Hint":{"Treasure":{"x":1,"y":2}}}"#),
+        PushRaw(r#"{"EndOfRound":{"round":1}}"#),
+        PushRaw(r#"{"Challenge":{"SecretSumProduct":7}}"#),
+        Push(radar_with_target(5)),
+        Expect(json!({"Action": {"MoveTo": "Right"}})),
+    ]);
+    let server = ScriptedServer::start(vec![script]);
+
+    let (mut game_state, mut map) = shared_state();
+    let mut stream = connect(&server.address);
+    let mut player = player("Player-0");
+    assert!(register_player("Player-0", &String::from("token"), &mut stream).unwrap());
+
+    let turn = play(&mut player, &mut stream, &mut game_state, &mut map).unwrap();
+    assert!(matches!(turn.received, Message::RadarView(_)));
+    server.finish();
+}
+
+#[test]
+fn test_play_rejects_malformed_known_messages() {
+    let mut script = subscribe("Player-0");
+    script.push(PushRaw(r#"{"RadarView":"ieysGjGO8papd/b"}"#));
+    let server = ScriptedServer::start(vec![script]);
+
+    let (mut game_state, mut map) = shared_state();
+    let mut stream = connect(&server.address);
+    let mut player = player("Player-0");
+    assert!(register_player("Player-0", &String::from("token"), &mut stream).unwrap());
+
+    assert!(play(&mut player, &mut stream, &mut game_state, &mut map).is_err());
+    server.finish();
+}
+
+#[test]
+fn test_play_challenges() {
+    // Chaque joueur reçoit son secret, la réponse est la somme des secrets de l'équipe modulo 10
+    let mut first = subscribe("Player-0");
+    first.extend([
+        Push(Message::Hint(Hint::Secret(20))),
+        Push(Message::Challenge(Challenge::SecretSumModulo(10))),
+        Expect(json!({"Action": {"SolveChallenge": {"answer": "2"}}})),
+        // Réponse refusée : le client recalcule et réessaie
+        Push(Message::ActionError(ActionError::InvalidChallengeSolution)),
+        Expect(json!({"Action": {"SolveChallenge": {"answer": "2"}}})),
+        Push(radar_with_target(7)),
+        Expect(json!({"Action": {"MoveTo": "Back"}})),
+    ]);
+    let mut second = subscribe("Player-1");
+    second.push(Push(Message::Hint(Hint::Secret(22))));
+    let server = ScriptedServer::start(vec![first, second]);
+
+    let (mut game_state, mut map) = shared_state();
+    let token = String::from("token");
+    let mut first_stream = connect(&server.address);
+    let mut second_stream = connect(&server.address);
+    let (mut first_player, mut second_player) = (player("Player-0"), player("Player-1"));
+    assert!(register_player("Player-0", &token, &mut first_stream).unwrap());
+    assert!(register_player("Player-1", &token, &mut second_stream).unwrap());
+
+    play(&mut first_player, &mut first_stream, &mut game_state, &mut map).unwrap();
+    play(&mut second_player, &mut second_stream, &mut game_state, &mut map).unwrap();
+    for _ in 0..3 {
+        play(&mut first_player, &mut first_stream, &mut game_state, &mut map).unwrap();
+    }
+    server.finish();
+
+    let game_state = game_state.lock().unwrap();
+    assert_eq!(game_state.stats["Player-0"].challenges_answered, 2);
+    assert_eq!(game_state.stats["Player-0"].challenge_failures, 1);
+}
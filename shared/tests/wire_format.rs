@@ -0,0 +1,227 @@
+// Format JSON exact attendu par le serveur de référence : chaque variante est comparée à un fichier
+// de référence (`tests/fixtures/*.json`). Un changement d'attribut serde qui casserait
+// l'interopérabilité fait échouer ces tests. Une nouvelle variante doit être ajoutée à la main
+// aux listes de valeurs ci-dessous et à sa référence : rien ne l'y oblige, seule une référence
+// sans valeur correspondante est détectée
+
+use std::{collections::BTreeSet, fs};
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+use shared::{
+    enums::{
+        Action, ActionError, Challenge, Hint, Message, RegisterTeamResult, RegistrationError, SubscribePlayerResult,
+    },
+    game_engine::Direction,
+    radar_view::decode_radarview,
+    structs::{RegisterTeam, SubscribePlayer},
+};
+
+struct Fixture {
+    file: &'static str,
+    values: Map<String, Value>,
+    checked: BTreeSet<String>,
+}
+
+impl Fixture {
+    fn load(file: &'static str) -> Self {
+        let path = format!("{}/tests/fixtures/{file}", env!("CARGO_MANIFEST_DIR"));
+        let text = fs::read_to_string(&path).unwrap_or_else(|err| panic!("{path}: {err}"));
+        Self {
+            file,
+            values: serde_json::from_str(&text).unwrap(),
+            checked: BTreeSet::new(),
+        }
+    }
+
+    // La valeur s'encode exactement comme la référence, et la référence se relit à l'identique
+    fn check<T: Serialize + DeserializeOwned>(&mut self, case: String, value: &T) {
+        let expected = self
+            .values
+            .get(&case)
+            .unwrap_or_else(|| panic!("{}: no fixture for {case}", self.file));
+
+        assert_eq!(&serde_json::to_value(value).unwrap(), expected, "{}: {case}", self.file);
+        let decoded: T = serde_json::from_value(expected.clone())
+            .unwrap_or_else(|err| panic!("{}: {case} does not decode: {err}", self.file));
+        assert_eq!(&serde_json::to_value(&decoded).unwrap(), expected, "{}: {case}", self.file);
+
+        self.checked.insert(case);
+    }
+
+    // Aucune référence ne doit rester sans variante correspondante
+    fn finish(self) {
+        let unchecked: Vec<_> = self.values.keys().filter(|case| !self.checked.contains(*case)).collect();
+        assert!(unchecked.is_empty(), "{}: unchecked fixtures {unchecked:?}", self.file);
+    }
+}
+
+// Les `match` sont exhaustifs : une nouvelle variante ne compile pas sans son nom de cas ici, mais
+// elle n'est testée qu'une fois ajoutée aux listes de valeurs des tests
+fn message_case(message: &Message) -> &'static str {
+    match message {
+        Message::RegisterTeam(_) => "RegisterTeam",
+        Message::RegisterTeamResult(_) => "RegisterTeamResult",
+        Message::SubscribePlayer(_) => "SubscribePlayer",
+        Message::SubscribePlayerResult(_) => "SubscribePlayerResult",
+        Message::RadarView(_) => "RadarView",
+        Message::Action(_) => "Action",
+        Message::Challenge(_) => "Challenge",
+        Message::ActionError(_) => "ActionError",
+        Message::Hint(_) => "Hint",
+    }
+}
+
+fn action_case(action: &Action) -> String {
+    match action {
+        Action::MoveTo(direction) => format!("MoveTo.{direction:?}"),
+        Action::SolveChallenge { .. } => String::from("SolveChallenge"),
+    }
+}
+
+fn action_error_case(error: &ActionError) -> String {
+    match error {
+        ActionError::CannotPassThroughWall
+        | ActionError::CannotPassThroughOpponent
+        | ActionError::NoRunningChallenge
+        | ActionError::SolveChallengeFirst
+        | ActionError::InvalidChallengeSolution => format!("{error:?}"),
+    }
+}
+
+fn challenge_case(challenge: &Challenge) -> String {
+    match challenge {
+        Challenge::SecretSumModulo(_) => String::from("SecretSumModulo"),
+    }
+}
+
+fn hint_case(hint: &Hint) -> String {
+    match hint {
+        Hint::RelativeCompass { .. } => String::from("RelativeCompass"),
+        Hint::GridSize { .. } => String::from("GridSize"),
+        Hint::Secret(_) => String::from("Secret"),
+    }
+}
+
+fn registration_error_case(error: &RegistrationError) -> String {
+    match error {
+        RegistrationError::AlreadyRegistered
+        | RegistrationError::InvalidName
+        | RegistrationError::InvalidRegistrationToken
+        | RegistrationError::TooManyPlayers => format!("RegistrationError.{error:?}"),
+    }
+}
+
+fn register_team_ok() -> RegisterTeamResult {
+    RegisterTeamResult::Ok {
+        expected_players: 3,
+        registration_token: String::from("SECRET"),
+    }
+}
+
+#[test]
+fn test_message_wire_format() {
+    let mut fixture = Fixture::load("message.json");
+    let messages = [
+        Message::RegisterTeam(RegisterTeam {
+            name: String::from("curious_broccoli"),
+        }),
+        Message::RegisterTeamResult(register_team_ok()),
+        Message::SubscribePlayer(SubscribePlayer {
+            name: String::from("dark_salad"),
+            registration_token: String::from("SECRET"),
+        }),
+        Message::SubscribePlayerResult(SubscribePlayerResult::Ok),
+        Message::RadarView(decode_radarview("ieysGjGO8papd/a").unwrap()),
+        Message::Action(Action::MoveTo(Direction::Front)),
+        Message::Challenge(Challenge::SecretSumModulo(7)),
+        Message::ActionError(ActionError::CannotPassThroughWall),
+        Message::Hint(Hint::Secret(1234)),
+    ];
+
+    for message in &messages {
+        fixture.check(message_case(message).to_string(), message);
+    }
+    fixture.finish();
+}
+
+#[test]
+fn test_registration_wire_format() {
+    let mut fixture = Fixture::load("registration.json");
+
+    fixture.check(String::from("RegisterTeamResult.Ok"), &register_team_ok());
+    fixture.check(
+        String::from("RegisterTeamResult.Err"),
+        &RegisterTeamResult::Err(RegistrationError::AlreadyRegistered),
+    );
+    fixture.check(String::from("SubscribePlayerResult.Ok"), &SubscribePlayerResult::Ok);
+    fixture.check(
+        String::from("SubscribePlayerResult.Err"),
+        &SubscribePlayerResult::Err(RegistrationError::InvalidRegistrationToken),
+    );
+
+    for error in [
+        RegistrationError::AlreadyRegistered,
+        RegistrationError::InvalidName,
+        RegistrationError::InvalidRegistrationToken,
+        RegistrationError::TooManyPlayers,
+    ] {
+        fixture.check(registration_error_case(&error), &error);
+    }
+    fixture.finish();
+}
+
+#[test]
+fn test_action_wire_format() {
+    let mut fixture = Fixture::load("action.json");
+    let actions = [
+        Action::MoveTo(Direction::Front),
+        Action::MoveTo(Direction::Right),
+        Action::MoveTo(Direction::Back),
+        Action::MoveTo(Direction::Left),
+        Action::SolveChallenge {
+            answer: String::from("42"),
+        },
+    ];
+
+    for action in &actions {
+        fixture.check(action_case(action), action);
+    }
+    fixture.finish();
+}
+
+#[test]
+fn test_action_error_wire_format() {
+    let mut fixture = Fixture::load("action_error.json");
+    let errors = [
+        ActionError::CannotPassThroughWall,
+        ActionError::CannotPassThroughOpponent,
+        ActionError::NoRunningChallenge,
+        ActionError::SolveChallengeFirst,
+        ActionError::InvalidChallengeSolution,
+    ];
+
+    for error in &errors {
+        fixture.check(action_error_case(error), error);
+    }
+    fixture.finish();
+}
+
+#[test]
+fn test_challenge_and_hint_wire_format() {
+    let mut fixture = Fixture::load("challenge.json");
+    let challenge = Challenge::SecretSumModulo(7);
+    fixture.check(challenge_case(&challenge), &challenge);
+    fixture.finish();
+
+    let mut fixture = Fixture::load("hint.json");
+    let hints = [
+        Hint::RelativeCompass { angle: 12.5 },
+        Hint::GridSize { columns: 10, rows: 12 },
+        Hint::Secret(1234),
+    ];
+    for hint in &hints {
+        fixture.check(hint_case(hint), hint);
+    }
+    fixture.finish();
+}